@@ -1,32 +1,36 @@
 extern crate structopt;
+extern crate util;
 
-use std::collections::HashSet;
-use std::path::PathBuf;
+use std::collections::HashMap;
 use structopt::StructOpt;
+use util::runner::{Day, RunnerOpt};
 
 #[derive(StructOpt, Debug)]
 struct Opt {
-    /// Starting frequency
-    #[structopt(short = "s", long = "start", default_value = "0")]
-    start: i32,
+    /// Starting frequency, overrides `start` in params.toml
+    #[structopt(short = "s", long = "start")]
+    start: Option<i32>,
 
-    /// Input file with frequency changes
-    #[structopt(parse(from_os_str))]
-    input_file: PathBuf,
+    #[structopt(flatten)]
+    runner: RunnerOpt,
 }
 
 fn main() {
-    let opt = Opt::from_args();
+    let mut opt = Opt::from_args();
 
-    let start = opt.start;
-    let input = parse_file_as_i32(&opt.input_file)
-        .expect("Could not process input file");
-
-    // Puzzle 1
-    println!("*** Puzzle 1: end result = {}", puzzle_1(start, &input));
+    if let Some(start) = opt.start {
+        opt.runner.set.push(format!("start={}", start));
+    }
 
-    // Puzzle 2
-    println!("*** Puzzle 2: first duplicate frequency = {}", puzzle_2(start, &input));
+    Day::with_params(env!("CARGO_MANIFEST_DIR"), |input, params| {
+            let changes = parse_as_i32(input).expect("Could not process input file");
+            (params.require::<i32>("start"), changes)
+        })
+        // Puzzle 1: end result
+        .puzzle(|(start, changes)| puzzle_1(*start, changes))
+        // Puzzle 2: first duplicate frequency
+        .try_puzzle(|(start, changes)| puzzle_2(*start, changes))
+        .run_with(opt.runner);
 }
 
 /// returns the resulting frequency after all `changes` are applied to `start`
@@ -35,29 +39,99 @@ fn puzzle_1(start: i32, changes: &Vec<i32>) -> i32 {
 }
 
 /// continuously applies `changes` to `start` and returns first duplicate frequency
-fn puzzle_2(start: i32, changes: &Vec<i32>) -> i32 {
-    let mut curr = start;
-    let mut prev_freqs: HashSet<i32> = HashSet::new();
+fn puzzle_2(start: i32, changes: &Vec<i32>) -> Result<i64, String> {
+    find_repeat(start, changes)
+        .map(|repeat| repeat.frequency)
+}
 
-    prev_freqs.insert(curr);
+/// The first frequency that is reached twice, `cycle` is the pass through the list of changes
+/// (starting at 0) and `offset` the index of the change that reaches it.
+#[derive(Debug, PartialEq)]
+struct Repeat {
+    frequency: i64,
+    cycle: usize,
+    offset: usize,
+}
 
-    loop {
-        for change in changes {
-            curr = curr + change;
+/// Finds the first repeated frequency without simulating every cycle.
+///
+/// Let `s_i` be the frequency after `i` changes of the first cycle (`s_0` is `start`) and `drift`
+/// the sum of all changes. After `k` more cycles the frequency at index `i` is `s_i + k * drift`,
+/// so `s_i` can only ever reach `s_j` if both are equal modulo `drift`. Within a group of equal
+/// residues, sorted by frequency, `s_i` first reaches its neighbour in the direction of the drift.
+/// The repeat that happens after the fewest changes wins.
+fn find_repeat(start: i32, changes: &[i32]) -> Result<Repeat, String> {
+    let n = changes.len();
+
+    if n == 0 {
+        return Err("There are no changes, the frequency never repeats".to_string());
+    }
 
-            if prev_freqs.contains(&curr) {
-                return curr;
-            }
+    let mut prefix_sums = Vec::with_capacity(n);
+    let mut curr = i64::from(start);
+
+    for change in changes {
+        prefix_sums.push(curr);
+        curr += i64::from(*change);
+    }
+    let drift = curr - i64::from(start);
+
+    // a frequency can repeat within the first cycle
+    let mut first_seen = HashMap::new();
+
+    for (i, freq) in prefix_sums.iter().enumerate() {
+        if first_seen.insert(*freq, i).is_some() {
+            return Ok(repeat_after(i, n, *freq));
+        }
+    }
+
+    // without drift the first cycle ends where it started
+    if drift == 0 {
+        return Ok(repeat_after(n, n, i64::from(start)));
+    }
+
+    let mut groups: HashMap<i64, Vec<(i64, usize)>> = HashMap::new();
+
+    for (i, freq) in prefix_sums.iter().enumerate() {
+        groups.entry(freq.rem_euclid(drift.abs()))
+            .or_default()
+            .push((*freq, i));
+    }
 
-            prev_freqs.insert(curr);
+    // (changes applied, frequency reached)
+    let mut best: Option<(usize, i64)> = None;
+
+    for group in groups.values_mut() {
+        group.sort();
+
+        for pair in group.windows(2) {
+            let ((lower, lower_i), (upper, upper_i)) = (pair[0], pair[1]);
+
+            let (from_i, to) = if drift > 0 { (lower_i, upper) } else { (upper_i, lower) };
+            let cycles = ((upper - lower) / drift.abs()) as usize;
+            let applied = cycles * n + from_i;
+
+            if best.is_none_or(|(best_applied, _)| applied < best_applied) {
+                best = Some((applied, to));
+            }
         }
     }
+
+    match best {
+        Some((applied, freq))   => Ok(repeat_after(applied, n, freq)),
+        None                    => Err(format!("No frequency ever repeats, every cycle drifts {} further", drift)),
+    }
 }
 
-fn parse_file_as_i32(file: &PathBuf) -> Result<Vec<i32>, String> {
-    let content = std::fs::read_to_string(&file)
-        .map_err(|e| e.to_string())?;
+fn repeat_after(applied: usize, n: usize, frequency: i64) -> Repeat {
+    Repeat {
+        frequency,
+        cycle: (applied - 1) / n,
+        offset: (applied - 1) % n,
+    }
+}
 
+fn parse_as_i32(content: &str) -> Result<Vec<i32>, String> {
     content.lines()
         .map(|line| line.parse::<i32>().map_err(|e| e.to_string()))
         .collect()
@@ -76,24 +150,77 @@ mod tests {
 
     #[test]
     fn puzzle_2_examples() {
-        assert_eq!(puzzle_2(0, &vec![ 1, -1]),  0);
-        assert_eq!(puzzle_2(0, &vec![ 3,  3,  4, -2, -4]), 10);
-        assert_eq!(puzzle_2(0, &vec![-6,  3,  8,  5, -6]),  5);
-        assert_eq!(puzzle_2(0, &vec![ 7,  7, -2, -7, -4]), 14);
+        assert_eq!(puzzle_2(0, &vec![ 1, -1]),  Ok(0));
+        assert_eq!(puzzle_2(0, &vec![ 3,  3,  4, -2, -4]), Ok(10));
+        assert_eq!(puzzle_2(0, &vec![-6,  3,  8,  5, -6]),  Ok(5));
+        assert_eq!(puzzle_2(0, &vec![ 7,  7, -2, -7, -4]), Ok(14));
+    }
+
+    #[test]
+    fn find_repeat_position() {
+        assert_eq!(find_repeat(0, &[1, -1]), Ok(Repeat { frequency: 0, cycle: 0, offset: 1 }));
+        assert_eq!(find_repeat(0, &[3, 3, 4, -2, -4]), Ok(Repeat { frequency: 10, cycle: 1, offset: 1 }));
+        assert_eq!(find_repeat(5, &[1, 1, -1]), Ok(Repeat { frequency: 6, cycle: 0, offset: 2 }));
+    }
+
+    #[test]
+    fn find_repeat_never() {
+        assert!(find_repeat(0, &[]).is_err());
+        assert!(find_repeat(0, &[1, 1]).is_err());
+        assert!(find_repeat(0, &[-5, 1, 1]).is_err());
+    }
+
+    /// applies the changes one by one, gives up after `limit` cycles
+    fn simulate(start: i32, changes: &[i32], limit: usize) -> Option<Repeat> {
+        let mut seen = HashMap::new();
+        let mut curr = i64::from(start);
+        seen.insert(curr, ());
+
+        for cycle in 0..limit {
+            for (offset, change) in changes.iter().enumerate() {
+                curr += i64::from(*change);
+
+                if seen.insert(curr, ()).is_some() {
+                    return Some(Repeat { frequency: curr, cycle, offset });
+                }
+            }
+        }
+        None
     }
 
     #[test]
-    fn parse_file_valid() {
-        assert_eq!(parse_file_as_i32(&PathBuf::from("valid_input.txt")).unwrap(), vec![45, 3, -3, 3]);
+    fn find_repeat_matches_simulation() {
+        let examples = vec![
+            vec![ 3,  3,  4, -2, -4],
+            vec![-6,  3,  8,  5, -6],
+            vec![ 7,  7, -2, -7, -4],
+            vec![ 5, -3,  2, -6],
+            vec![-4,  7, -1],
+            vec![10, -9],
+            vec![-2,  1, -2,  4],
+        ];
+
+        for changes in examples {
+            for start in -3..4 {
+                assert_eq!(find_repeat(start, &changes).ok(), simulate(start, &changes, 1000), "{:?} from {}", changes, start);
+            }
+        }
+    }
+
+    #[test]
+    fn find_repeat_input() {
+        let changes = parse_as_i32(include_str!("../input.txt")).unwrap();
+
+        assert_eq!(find_repeat(0, &changes).ok(), simulate(0, &changes, 1000));
     }
 
     #[test]
-    fn parse_file_invalid() {
-        assert!(parse_file_as_i32(&PathBuf::from("invalid_input.txt")).is_err());
+    fn parse_valid() {
+        assert_eq!(parse_as_i32(include_str!("../valid_input.txt")).unwrap(), vec![45, 3, -3, 3]);
     }
 
     #[test]
-    fn parse_file_non_existing() {
-        assert!(parse_file_as_i32(&PathBuf::from("does_not_exist.txt")).is_err());
+    fn parse_invalid() {
+        assert!(parse_as_i32(include_str!("../invalid_input.txt")).is_err());
     }
 }
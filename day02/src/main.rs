@@ -1,84 +1,43 @@
-use std::collections::HashMap;
+extern crate unicode_segmentation;
+extern crate util;
 
-fn main() {
-    let input = include_str!("../input.txt")
-        .split("\n")
-        .collect::<Vec<&str>>();
-
-    println!("Puzzle 1: {}", puzzle_1(&input));
-    println!("Puzzle 2: {}", puzzle_2(&input));
-}
-
-fn puzzle_1(input: &Vec<&str>) -> i32 {
-    let mut count_doubles = 0;
-    let mut count_triples = 0;
-
-    for id in input {
-        let occurances = count_occurances(id);
-
-        if occurances.values().find(|&&e| e == 2).is_some() {
-            count_doubles += 1;
-        }
-        if occurances.values().find(|&&e| e == 3).is_some() {
-            count_triples += 1;
-        }
-    }
-    count_doubles * count_triples
-}
+mod multiplicity;
+mod near_duplicates;
 
-fn count_occurances(id: &str) -> HashMap<char, i32> {
-    let mut occurances = HashMap::new();
+use multiplicity::Histogram;
+use near_duplicates::*;
+use util::runner::Day;
 
-    for c in id.chars() {
-        let count = occurances.entry(c).or_insert(0);
-        *count += 1;
-    }
-    occurances
+fn parse_input(input: &str) -> Vec<&str> {
+    input.split("\n")
+        .collect()
 }
 
-fn puzzle_2(input: &Vec<&str>) -> String {
-    let len = input.len();
-
-    for i in 0..len {
-        for j in i..len {
-            let id1 = input.get(i).unwrap();
-            let id2 = input.get(j).unwrap();
-
-            if differs_by_one_char(id1, id2) {
-                return filter_differences(id1, id2);
-            }
-        }
-    }
-    panic!("Found no solution.");
+fn main() {
+    Day::new(env!("CARGO_MANIFEST_DIR"), parse_input)
+        .puzzle(puzzle_1)
+        .try_puzzle(puzzle_2)
+        .run();
 }
 
-fn differs_by_one_char(str1: &str, str2: &str) -> bool {
-    str1.chars().zip(str2.chars())
-        .filter(|(c1, c2)| c1 != c2)
-        .count() == 1
+fn puzzle_1(input: &Vec<&str>) -> usize {
+    Histogram::new(input)
+        .checksum(&[2, 3])
 }
 
-fn filter_differences(str1: &str, str2: &str) -> String {
-    str1.chars().zip(str2.chars())
-        .filter(|c| c.0 == c.1)
-        .map(|(c1, _c2)| c1)    // just throw away half of tuple, unzip is having issue deducting types... 🙄
-        .collect()
+/// Returns the common letters of the two IDs that differ by exactly one character.
+fn puzzle_2(input: &Vec<&str>) -> Result<String, String> {
+    find_near_duplicates(input, 1)
+        .into_iter()
+        .next()
+        .map(|m| m.common)
+        .ok_or_else(|| "Found no IDs that differ by exactly one character".to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_count_occurances() {
-        let occurances = count_occurances("aabc");
-
-        assert_eq!(*occurances.get(&'a').unwrap(), 2);
-        assert_eq!(*occurances.get(&'b').unwrap(), 1);
-        assert_eq!(*occurances.get(&'c').unwrap(), 1);
-        assert!(occurances.get(&'d').is_none());
-    }
-
     #[test]
     fn test_puzzle_1() {
         let mut example = Vec::new();
@@ -93,23 +52,6 @@ mod tests {
         assert_eq!(puzzle_1(&example), 12);
     }
 
-    #[test]
-    fn test_differs_by_one_char() {
-        assert_eq!(differs_by_one_char("abcd", "abcd"), false);
-        assert_eq!(differs_by_one_char("abcd", "abcc"), true);
-        assert_eq!(differs_by_one_char("abcd", "abbb"), false);
-        assert_eq!(differs_by_one_char("abcd", "aaaa"), false);
-    }
-
-    #[test]
-    fn test_filter_differences() {
-        assert_eq!(filter_differences("abcd", "abcd"), "abcd");
-        assert_eq!(filter_differences("abcd", "abcc"), "abc");
-        assert_eq!(filter_differences("abcd", "abbb"), "ab");
-        assert_eq!(filter_differences("abcd", "aaaa"), "a");
-        assert_eq!(filter_differences("abcd", "aacd"), "acd");
-    }
-
     #[test]
     fn test_puzzle_2() {
         let mut example = Vec::new();
@@ -121,6 +63,11 @@ mod tests {
         example.push("axcye");
         example.push("wvxyz");
 
-        assert_eq!(puzzle_2(&example), "fgij".to_string());
+        assert_eq!(puzzle_2(&example), Ok("fgij".to_string()));
+    }
+
+    #[test]
+    fn test_puzzle_2_no_match() {
+        assert!(puzzle_2(&vec!["abc", "xyz", "ab"]).is_err());
     }
 }
@@ -0,0 +1,101 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Counts how often each letter occurs in an ID. A letter is a grapheme cluster, so `e` with a
+/// combining accent counts as one letter. Letters are not normalized: a precomposed `é` is a
+/// different letter than `e` with a combining accent.
+pub fn count_occurances(id: &str) -> HashMap<&str, usize> {
+    let mut occurances = HashMap::new();
+
+    for letter in id.graphemes(true) {
+        *occurances.entry(letter).or_insert(0) += 1;
+    }
+    occurances
+}
+
+/// Groups IDs by the multiplicities of their letters: the bucket of multiplicity 2 holds every
+/// ID with at least one letter that occurs exactly twice.
+#[derive(Debug, Default)]
+pub struct Histogram<'a> {
+    buckets: BTreeMap<usize, Vec<&'a str>>,
+}
+
+impl<'a> Histogram<'a> {
+    pub fn new(ids: &[&'a str]) -> Histogram<'a> {
+        let mut buckets: BTreeMap<usize, Vec<&'a str>> = BTreeMap::new();
+
+        for id in ids {
+            let multiplicities: BTreeSet<usize> = count_occurances(id).values()
+                .cloned()
+                .collect();
+
+            for multiplicity in multiplicities {
+                buckets.entry(multiplicity)
+                    .or_default()
+                    .push(id);
+            }
+        }
+
+        Histogram { buckets }
+    }
+
+    /// The IDs with a letter that occurs exactly `multiplicity` times, in input order.
+    pub fn ids(&self, multiplicity: usize) -> &[&'a str] {
+        self.buckets.get(&multiplicity)
+            .map(|ids| ids.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// The amount of IDs with a letter that occurs exactly `multiplicity` times.
+    pub fn count(&self, multiplicity: usize) -> usize {
+        self.ids(multiplicity).len()
+    }
+
+    /// Multiplies the counts of the given multiplicities, `[2, 3]` gives the checksum of the
+    /// puzzle.
+    pub fn checksum(&self, multiplicities: &[usize]) -> usize {
+        multiplicities.iter()
+            .map(|&multiplicity| self.count(multiplicity))
+            .product()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_occurances() {
+        let occurances = count_occurances("aabc");
+
+        assert_eq!(occurances.get("a"), Some(&2));
+        assert_eq!(occurances.get("b"), Some(&1));
+        assert_eq!(occurances.get("c"), Some(&1));
+        assert!(!occurances.contains_key("d"));
+    }
+
+    #[test]
+    fn test_count_occurances_unicode() {
+        let occurances = count_occurances("\u{e9}\u{e9}e\u{301}🎁🎁");
+
+        assert_eq!(occurances.get("\u{e9}"), Some(&2));
+        assert_eq!(occurances.get("e\u{301}"), Some(&1));
+        assert_eq!(occurances.get("🎁"), Some(&2));
+        assert_eq!(occurances.len(), 3);
+    }
+
+    #[test]
+    fn test_histogram() {
+        let ids = vec!["abcdef", "bababc", "abbcde", "abcccd", "aabcdd", "abcdee", "ababab"];
+        let histogram = Histogram::new(&ids);
+
+        assert_eq!(histogram.ids(2), &["bababc", "abbcde", "aabcdd", "abcdee"]);
+        assert_eq!(histogram.ids(3), &["bababc", "abcccd", "ababab"]);
+        assert_eq!(histogram.ids(4), &[] as &[&str]);
+        assert_eq!(histogram.count(1), 6);
+
+        assert_eq!(histogram.checksum(&[2, 3]), 12);
+        assert_eq!(histogram.checksum(&[1, 2, 3]), 72);
+        assert_eq!(histogram.checksum(&[]), 1);
+    }
+}
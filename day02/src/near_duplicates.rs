@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+/// Two IDs that differ at exactly the given positions.
+#[derive(Debug, PartialEq)]
+pub struct Match<'a> {
+    pub first: &'a str,
+    pub second: &'a str,
+    pub positions: Vec<usize>,
+    pub common: String,
+}
+
+/// Finds every pair of IDs that differ in exactly `k` characters (Hamming distance).
+///
+/// Each ID is indexed once for every way of masking `k` of its positions. Two IDs with exactly
+/// `k` differences end up in the same bucket when the differing positions are masked, and in
+/// no other bucket, so every pair is found once. For small `k` this is near-linear in the
+/// number of IDs instead of comparing every pair.
+///
+/// IDs of different lengths never match. Matches are ordered by the position of their IDs in
+/// the input.
+pub fn find_near_duplicates<'a>(ids: &[&'a str], k: usize) -> Vec<Match<'a>> {
+    let chars: Vec<Vec<char>> = ids.iter()
+        .map(|id| id.chars().collect())
+        .collect();
+
+    let mut buckets: HashMap<(Vec<usize>, Vec<char>), Vec<usize>> = HashMap::new();
+
+    for (index, id) in chars.iter().enumerate() {
+        for masked in combinations(id.len(), k) {
+            let key: Vec<char> = id.iter()
+                .enumerate()
+                .map(|(i, &c)| if masked.contains(&i) { '\0' } else { c })
+                .collect();
+
+            buckets.entry((masked, key))
+                .or_default()
+                .push(index);
+        }
+    }
+
+    let mut pairs = Vec::new();
+
+    for ((masked, _), indices) in buckets {
+        for (n, &i) in indices.iter().enumerate() {
+            for &j in &indices[n + 1..] {
+                // IDs that differ in fewer positions share this bucket as well
+                if hamming_distance(ids[i], ids[j]) == Some(k) {
+                    pairs.push((i, j, masked.clone()));
+                }
+            }
+        }
+    }
+
+    pairs.sort();
+
+    pairs.into_iter()
+        .map(|(i, j, positions)| {
+            Match {
+                first: ids[i],
+                second: ids[j],
+                common: common_letters(ids[i], ids[j]),
+                positions,
+            }
+        })
+        .collect()
+}
+
+/// The amount of positions at which two IDs differ, None if they don't have the same length.
+pub fn hamming_distance(id1: &str, id2: &str) -> Option<usize> {
+    if id1.chars().count() != id2.chars().count() {
+        return None;
+    }
+
+    Some(id1.chars().zip(id2.chars())
+        .filter(|(c1, c2)| c1 != c2)
+        .count())
+}
+
+/// The characters that are the same, at the same position, in both IDs.
+pub fn common_letters(id1: &str, id2: &str) -> String {
+    id1.chars().zip(id2.chars())
+        .filter(|(c1, c2)| c1 == c2)
+        .map(|(c1, _)| c1)
+        .collect()
+}
+
+/// All ways to choose `k` positions out of `n`, in lexicographical order.
+fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    if k > n {
+        return Vec::new();
+    }
+
+    let mut result = Vec::new();
+    let mut current: Vec<usize> = (0..k).collect();
+
+    loop {
+        result.push(current.clone());
+
+        // find the rightmost position that can still move to the right
+        let i = match (0..k).rev().find(|&i| current[i] < n - k + i) {
+            Some(i) => i,
+            None    => return result,
+        };
+
+        current[i] += 1;
+        for j in i + 1..k {
+            current[j] = current[j - 1] + 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combinations() {
+        assert_eq!(combinations(3, 0), vec![vec![]]);
+        assert_eq!(combinations(3, 1), vec![vec![0], vec![1], vec![2]]);
+        assert_eq!(combinations(4, 2), vec![vec![0, 1], vec![0, 2], vec![0, 3], vec![1, 2], vec![1, 3], vec![2, 3]]);
+        assert_eq!(combinations(2, 3), Vec::<Vec<usize>>::new());
+    }
+
+    #[test]
+    fn test_hamming_distance() {
+        assert_eq!(hamming_distance("abcd", "abcd"), Some(0));
+        assert_eq!(hamming_distance("abcd", "abcc"), Some(1));
+        assert_eq!(hamming_distance("abcd", "abbb"), Some(2));
+        assert_eq!(hamming_distance("abcd", "aaaa"), Some(3));
+        assert_eq!(hamming_distance("abcd", "abc"), None);
+        assert_eq!(hamming_distance("äbc", "abc"), Some(1));
+    }
+
+    #[test]
+    fn test_common_letters() {
+        assert_eq!(common_letters("abcd", "abcd"), "abcd");
+        assert_eq!(common_letters("abcd", "abcc"), "abc");
+        assert_eq!(common_letters("abcd", "abbb"), "ab");
+        assert_eq!(common_letters("abcd", "aaaa"), "a");
+        assert_eq!(common_letters("abcd", "aacd"), "acd");
+    }
+
+    #[test]
+    fn test_find_near_duplicates() {
+        let ids = vec!["abcde", "fghij", "klmno", "pqrst", "fguij", "axcye", "wvxyz"];
+
+        assert_eq!(find_near_duplicates(&ids, 1), vec![
+            Match { first: "fghij", second: "fguij", positions: vec![2], common: "fgij".to_string() },
+        ]);
+        assert_eq!(find_near_duplicates(&ids, 2), vec![
+            Match { first: "abcde", second: "axcye", positions: vec![1, 3], common: "ace".to_string() },
+        ]);
+    }
+
+    #[test]
+    fn test_find_near_duplicates_all_pairs() {
+        let ids = vec!["abc", "abd", "xbc", "abe", "ab", "abcd"];
+
+        let pairs: Vec<(&str, &str)> = find_near_duplicates(&ids, 1).iter()
+            .map(|m| (m.first, m.second))
+            .collect();
+
+        assert_eq!(pairs, vec![("abc", "abd"), ("abc", "xbc"), ("abc", "abe"), ("abd", "abe")]);
+        assert_eq!(find_near_duplicates(&ids, 0), vec![]);
+        assert_eq!(find_near_duplicates(&["abc", "abc"], 0).len(), 1);
+    }
+
+    #[test]
+    fn test_find_near_duplicates_brute_force() {
+        let ids: Vec<&str> = include_str!("../input.txt").lines().collect();
+
+        for k in 1..3 {
+            let mut expected = Vec::new();
+            for i in 0..ids.len() {
+                for j in i + 1..ids.len() {
+                    if hamming_distance(ids[i], ids[j]) == Some(k) {
+                        expected.push((ids[i], ids[j]));
+                    }
+                }
+            }
+
+            let found: Vec<(&str, &str)> = find_near_duplicates(&ids, k).iter()
+                .map(|m| (m.first, m.second))
+                .collect();
+
+            assert_eq!(found, expected);
+        }
+    }
+}
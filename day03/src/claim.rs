@@ -0,0 +1,94 @@
+use nom::types::CompleteStr;
+use util::nom_utils::parse_i64;
+
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct Claim {
+    pub id: i32,
+    pub dist_left: i64,
+    pub dist_top: i64,
+    pub width: i64,
+    pub height: i64,
+}
+
+named!(parse_i32<CompleteStr, i32>,
+    map_res!(
+        take_while_s!(char::is_numeric),
+        |s: CompleteStr| s.parse::<i32>()
+    )
+);
+
+// example: "#1 @ 1,3: 4x4"
+named!(parse_claim<CompleteStr, Claim>,
+    do_parse!(
+        tag!("#")               >>
+        id: parse_i32           >>
+        tag!(" @ ")             >>
+        dist_left: parse_i64    >>
+        tag!(",")               >>
+        dist_top: parse_i64     >>
+        tag!(": ")              >>
+        width: parse_i64        >>
+        tag!("x")               >>
+        height: parse_i64       >>
+
+        (Claim { id, dist_left, dist_top, width, height })
+    )
+);
+
+impl Claim {
+    pub fn from(line: &str) -> Claim {
+        parse_claim(CompleteStr(line))
+            .expect("Failed to parse claim")
+            .1
+    }
+
+    pub fn from_vec(lines: &Vec<&str>) -> Vec<Claim> {
+        lines.iter()
+            .map(|line| Claim::from(line))
+            .collect()
+    }
+
+    pub fn left(&self) -> i64 {
+        self.dist_left
+    }
+
+    pub fn right(&self) -> i64 {
+        self.dist_left + self.width
+    }
+
+    pub fn top(&self) -> i64 {
+        self.dist_top
+    }
+
+    pub fn bottom(&self) -> i64 {
+        self.dist_top + self.height
+    }
+
+    /// A claim without area can't overlap with anything.
+    pub fn is_empty(&self) -> bool {
+        self.width <= 0 || self.height <= 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claim_from() {
+        assert_eq!(Claim::from("#1 @ 1,3: 4x4"), Claim{ id: 1, dist_left: 1, dist_top: 3, width: 4, height: 4 });
+        assert_eq!(Claim::from("#2 @ 3,1: 4x4"), Claim{ id: 2, dist_left: 3, dist_top: 1, width: 4, height: 4 });
+        assert_eq!(Claim::from("#3 @ 5,5: 2x2"), Claim{ id: 3, dist_left: 5, dist_top: 5, width: 2, height: 2 });
+        assert_eq!(Claim::from("#1 @ 662,777: 18x27"), Claim{ id: 1, dist_left: 662, dist_top: 777, width: 18, height: 27 });
+        assert_eq!(Claim::from("#4 @ -5,3000000000: 1x2"), Claim{ id: 4, dist_left: -5, dist_top: 3_000_000_000, width: 1, height: 2 });
+    }
+
+    #[test]
+    fn test_claim_bounds() {
+        let claim = Claim::from("#1 @ 1,3: 4x5");
+
+        assert_eq!((claim.left(), claim.right(), claim.top(), claim.bottom()), (1, 5, 3, 8));
+        assert!(!claim.is_empty());
+        assert!(Claim::from("#2 @ 1,3: 0x5").is_empty());
+    }
+}
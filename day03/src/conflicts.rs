@@ -0,0 +1,239 @@
+use claim::Claim;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// An overlap with another claim.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conflict {
+    pub other: i32,
+    pub area: i64,
+}
+
+/// Which claims overlap with which, and by how much.
+#[derive(Debug)]
+pub struct ConflictGraph {
+    conflicts: BTreeMap<i32, Vec<Conflict>>,
+}
+
+impl ConflictGraph {
+    /// Sweeps over the claims from left to right, a claim is only compared with the claims that
+    /// are still active (their right edge is past its left edge). Apart from sorting, the work
+    /// depends on how many claims are stacked on top of each other, not on the size of the fabric.
+    pub fn new(claims: &[Claim]) -> ConflictGraph {
+        let mut conflicts: BTreeMap<i32, Vec<Conflict>> = claims.iter()
+            .map(|claim| (claim.id, Vec::new()))
+            .collect();
+
+        let mut sorted: Vec<&Claim> = claims.iter()
+            .filter(|claim| !claim.is_empty())
+            .collect();
+        sorted.sort_by_key(|claim| claim.left());
+
+        let mut active: Vec<&Claim> = Vec::new();
+
+        for claim in sorted {
+            active.retain(|other| other.right() > claim.left());
+
+            for other in &active {
+                let width = claim.right().min(other.right()) - claim.left().max(other.left());
+                let height = claim.bottom().min(other.bottom()) - claim.top().max(other.top());
+
+                if width > 0 && height > 0 {
+                    let area = width * height;
+
+                    conflicts.entry(claim.id).or_default().push(Conflict { other: other.id, area });
+                    conflicts.entry(other.id).or_default().push(Conflict { other: claim.id, area });
+                }
+            }
+            active.push(claim);
+        }
+
+        for list in conflicts.values_mut() {
+            list.sort_by_key(|conflict| conflict.other);
+        }
+
+        ConflictGraph { conflicts }
+    }
+
+    /// The claims that overlap with claim `id`, ordered by id.
+    pub fn conflicts(&self, id: i32) -> &[Conflict] {
+        self.conflicts.get(&id)
+            .map(|conflicts| conflicts.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// All claims that don't overlap with any other claim, ordered by id.
+    pub fn free_claims(&self) -> Vec<i32> {
+        self.conflicts.iter()
+            .filter(|(_, conflicts)| conflicts.is_empty())
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Groups of claims that are connected through overlaps, each ordered by id. Claims without
+    /// conflicts are not part of a cluster.
+    pub fn clusters(&self) -> Vec<Vec<i32>> {
+        let mut seen = BTreeSet::new();
+        let mut clusters = Vec::new();
+
+        for (&id, conflicts) in &self.conflicts {
+            if conflicts.is_empty() || !seen.insert(id) {
+                continue;
+            }
+
+            let mut cluster = vec![id];
+            let mut todo = vec![id];
+
+            while let Some(current) = todo.pop() {
+                for conflict in self.conflicts(current) {
+                    if seen.insert(conflict.other) {
+                        cluster.push(conflict.other);
+                        todo.push(conflict.other);
+                    }
+                }
+            }
+
+            cluster.sort();
+            clusters.push(cluster);
+        }
+        clusters
+    }
+
+    /// A report of every claim, its conflicts and the clusters.
+    pub fn report(&self, format: ReportFormat) -> String {
+        let clusters = self.clusters();
+
+        let mut cluster_of = BTreeMap::new();
+        for (i, cluster) in clusters.iter().enumerate() {
+            for id in cluster {
+                cluster_of.insert(*id, i + 1);
+            }
+        }
+
+        let mut report = String::new();
+
+        match format {
+            ReportFormat::Text  => {
+                for (id, conflicts) in &self.conflicts {
+                    if conflicts.is_empty() {
+                        writeln!(report, "#{}: no conflicts", id).unwrap();
+                        continue;
+                    }
+
+                    let total: i64 = conflicts.iter().map(|conflict| conflict.area).sum();
+                    let others: Vec<String> = conflicts.iter()
+                        .map(|conflict| format!("#{} ({} in²)", conflict.other, conflict.area))
+                        .collect();
+
+                    writeln!(report, "#{}: cluster {}, overlaps {} in total with {}", id, cluster_of[id], total, others.join(", ")).unwrap();
+                }
+
+                writeln!(report).unwrap();
+                for (i, cluster) in clusters.iter().enumerate() {
+                    let ids: Vec<String> = cluster.iter().map(|id| format!("#{}", id)).collect();
+                    writeln!(report, "cluster {}: {}", i + 1, ids.join(", ")).unwrap();
+                }
+
+                let free: Vec<String> = self.free_claims().iter().map(|id| format!("#{}", id)).collect();
+                writeln!(report, "no conflicts: {}", free.join(", ")).unwrap();
+            }
+            ReportFormat::Csv   => {
+                writeln!(report, "claim,other,area,cluster").unwrap();
+
+                for (id, conflicts) in &self.conflicts {
+                    if conflicts.is_empty() {
+                        writeln!(report, "{},,,", id).unwrap();
+                    }
+                    for conflict in conflicts {
+                        writeln!(report, "{},{},{},{}", id, conflict.other, conflict.area, cluster_of[id]).unwrap();
+                    }
+                }
+            }
+        }
+        report
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReportFormat {
+    Text,
+    Csv,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<ReportFormat, String> {
+        match s {
+            "text"  => Ok(ReportFormat::Text),
+            "csv"   => Ok(ReportFormat::Csv),
+            _       => Err(format!("Unknown report format `{}`, expected text or csv", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use overlap::overlap_counts;
+
+    fn example() -> Vec<Claim> {
+        ["#1 @ 1,3: 4x4", "#2 @ 3,1: 4x4", "#3 @ 5,5: 2x2", "#4 @ 6,0: 3x2", "#5 @ 20,20: 2x2", "#6 @ 21,21: 3x3"]
+            .iter()
+            .map(|line| Claim::from(line))
+            .collect()
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let graph = ConflictGraph::new(&example());
+
+        assert_eq!(graph.conflicts(1), &[Conflict { other: 2, area: 4 }]);
+        assert_eq!(graph.conflicts(2), &[Conflict { other: 1, area: 4 }, Conflict { other: 4, area: 1 }]);
+        assert_eq!(graph.conflicts(3), &[]);
+        assert_eq!(graph.conflicts(6), &[Conflict { other: 5, area: 1 }]);
+        assert_eq!(graph.conflicts(99), &[]);
+    }
+
+    #[test]
+    fn test_clusters() {
+        let graph = ConflictGraph::new(&example());
+
+        assert_eq!(graph.clusters(), vec![vec![1, 2, 4], vec![5, 6]]);
+        assert_eq!(graph.free_claims(), vec![3]);
+    }
+
+    #[test]
+    fn test_conflicts_input() {
+        let input: Vec<&str> = include_str!("../input.txt").lines().collect();
+        let claims = Claim::from_vec(&input);
+        let graph = ConflictGraph::new(&claims);
+
+        let counts: Vec<usize> = claims.iter()
+            .map(|claim| graph.conflicts(claim.id).len())
+            .collect();
+
+        assert_eq!(counts, overlap_counts(&claims));
+    }
+
+    #[test]
+    fn test_report() {
+        let graph = ConflictGraph::new(&example());
+
+        let text = graph.report(ReportFormat::Text);
+        assert!(text.contains("#2: cluster 1, overlaps 5 in total with #1 (4 in²), #4 (1 in²)\n"));
+        assert!(text.contains("#3: no conflicts\n"));
+        assert!(text.contains("cluster 2: #5, #6\n"));
+        assert!(text.ends_with("no conflicts: #3\n"));
+
+        let csv = graph.report(ReportFormat::Csv);
+        assert!(csv.starts_with("claim,other,area,cluster\n1,2,4,1\n2,1,4,1\n2,4,1,1\n3,,,\n"));
+    }
+
+    #[test]
+    fn test_report_format() {
+        assert_eq!("csv".parse(), Ok(ReportFormat::Csv));
+        assert!("xml".parse::<ReportFormat>().is_err());
+    }
+}
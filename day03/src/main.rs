@@ -1,156 +1,78 @@
 #[macro_use]
 extern crate nom;
-
-use std::collections::HashSet;
-use std::iter::FromIterator;
-use nom::types::CompleteStr;
-
-#[derive(PartialEq, Eq, Hash, Debug)]
-struct Claim {
-    id: i32,
-    dist_left: i32,
-    dist_top: i32,
-    width: i32,
-    height: i32,
-}
-
-named!(parse_i32<CompleteStr, i32>,
-    map_res!(
-        take_while_s!(char::is_numeric),
-        |s: CompleteStr| s.parse::<i32>()
-    )
-);
-
-// example: "#1 @ 1,3: 4x4"
-named!(parse_claim<CompleteStr, Claim>,
-    do_parse!( 
-        tag!("#")               >>
-        id: parse_i32           >>
-        take!(3)                >>  // this hard-coded stuff is pretty poor...
-        dist_left: parse_i32    >>
-        take!(1)                >>
-        dist_top: parse_i32     >>
-        take!(2)                >>
-        width: parse_i32        >>
-        take!(1)                >>
-        height: parse_i32       >>
-
-        (Claim { id, dist_left, dist_top, width, height })
-    )
-);
-
-impl Claim {
-    fn from(line: &str) -> Claim {
-        parse_claim(CompleteStr(line))
-            .expect("Failed to parse claim")
-            .1
-    }
-
-    fn from_vec(lines: &Vec<&str>) -> Vec<Claim> {
-        lines.iter()
-            .map(|line| Claim::from(line))
-            .collect()
-    }
-}
-
-// TODO: tests for fabric
-struct Fabric {
-    width: i32,
-    height: i32,
-    square_inches: Vec<Vec<i32>>,   // a 2D vector of claim ids
+extern crate structopt;
+extern crate util;
+
+mod claim;
+mod conflicts;
+mod overlap;
+
+use claim::Claim;
+use conflicts::{ConflictGraph, ReportFormat};
+use overlap::*;
+use std::path::Path;
+use structopt::StructOpt;
+use util::runner::{Day, RunnerOpt};
+
+#[derive(StructOpt, Debug)]
+struct Opt {
+    /// Print which claims overlap instead of solving the puzzles: text or csv
+    #[structopt(long = "report")]
+    report: Option<ReportFormat>,
+
+    #[structopt(flatten)]
+    runner: RunnerOpt,
 }
 
-impl Fabric {
-    fn new(width: i32, height: i32) -> Fabric {
-        Fabric { width, height, square_inches: vec![vec![]; (width * height) as usize] }
-    }
-
-    fn apply(&mut self, claim: &Claim) {
-        let x_range = (claim.dist_left, claim.dist_left + claim.width);
-        let y_range = (claim.dist_top, claim.dist_top + claim.height);
-
-        for x in x_range.0..x_range.1 {
-            for y in y_range.0..y_range.1 {
-                let index = (x + (y * self.width)) as usize;
-                let square_inch = self.square_inches.get_mut(index)
-                    .expect("Claim not in range");   // should not be able to happen
-
-                square_inch.push(claim.id);
-            }
-        }
-    }
-
-    fn apply_all(&mut self, claims: &Vec<Claim>) {
-        for claim in claims {
-            self.apply(&claim);
-        }
-    }
-}
-
-fn get_input() -> Vec<&'static str> {
-    include_str!("../input.txt")
-        .split("\n")
+fn parse_input(input: &str) -> Vec<&str> {
+    input.split("\n")
         .filter(|s| s.len() > 0)
         .collect()
 }
 
 fn main() {
-    let input = get_input();
+    let opt = Opt::from_args();
 
-    println!("Puzzle 1: {}", puzzle_1(&input));
-    println!("Puzzle 2: {}", puzzle_2(&input));
-}
+    if let Some(format) = opt.report {
+        let file = opt.runner.input
+            .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("input.txt"));
 
-fn puzzle_1(input: &Vec<&str>) -> i32 {
-    let claims = Claim::from_vec(&input);
+        let input = std::fs::read_to_string(&file)
+            .expect("Could not read input file");
+        let claims = Claim::from_vec(&parse_input(&input));
 
-    let mut fabric = Fabric::new(1000, 1000);
-    fabric.apply_all(&claims);
+        print!("{}", ConflictGraph::new(&claims).report(format));
+        return;
+    }
 
-    fabric.square_inches.iter()
-        .filter(|count| count.len() > 1)
-        .count() as i32
+    Day::new(env!("CARGO_MANIFEST_DIR"), parse_input)
+        .puzzle(puzzle_1)
+        .try_puzzle(puzzle_2)
+        .run_with(opt.runner);
 }
 
-fn puzzle_2(input: &Vec<&str>) -> i32 {
-    let claims = Claim::from_vec(&input);
-
-    let mut fabric = Fabric::new(1000, 1000);
-    fabric.apply_all(&claims);
+fn puzzle_1(input: &Vec<&str>) -> i64 {
+    let claims = Claim::from_vec(input);
 
-    let ids = HashSet::from_iter(
-        claims.iter()
-            .map(|claim| claim.id)
-    );
+    overlap_area(&claims)
+}
 
-    let ids_in_conflict = HashSet::<i32>::from_iter(
-        fabric.square_inches.iter()
-            .filter(|square_inch| square_inch.len() > 1)
-            .flatten()
-            .map(|id: &i32| id.clone())
-    );
+fn puzzle_2(input: &Vec<&str>) -> Result<i32, String> {
+    let claims = Claim::from_vec(input);
 
-    ids.difference(&ids_in_conflict)
-        .map(|id: &i32| id.clone())
-        .next()
-        .expect("Could not find solution")  // fails if difference is empty
+    non_overlapping(&claims)
+        .first()
+        .map(|claim| claim.id)
+        .ok_or_else(|| "Every claim overlaps with another claim".to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_claim_from() {
-        assert_eq!(Claim::from("#1 @ 1,3: 4x4"), Claim{ id: 1, dist_left: 1, dist_top: 3, width: 4, height: 4 });
-        assert_eq!(Claim::from("#2 @ 3,1: 4x4"), Claim{ id: 2, dist_left: 3, dist_top: 1, width: 4, height: 4 });
-        assert_eq!(Claim::from("#3 @ 5,5: 2x2"), Claim{ id: 3, dist_left: 5, dist_top: 5, width: 2, height: 2 });
-        assert_eq!(Claim::from("#1 @ 662,777: 18x27"), Claim{ id: 1, dist_left: 662, dist_top: 777, width: 18, height: 27 });
-    }
-
     #[test]
     fn test_claim_from_input() {
-        let input = get_input();
+        let input = parse_input(include_str!("../input.txt"));
         let claims = Claim::from_vec(&input);
 
         assert_eq!(claims.len(), input.len());
@@ -173,6 +95,11 @@ mod tests {
         example.push("#2 @ 3,1: 4x4");
         example.push("#3 @ 5,5: 2x2");
         
-        assert_eq!(puzzle_2(&example), 3);
+        assert_eq!(puzzle_2(&example), Ok(3));
+    }
+
+    #[test]
+    fn test_puzzle_2_no_solution() {
+        assert!(puzzle_2(&vec!["#1 @ 1,1: 2x2", "#2 @ 2,2: 2x2"]).is_err());
     }
 }
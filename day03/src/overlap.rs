@@ -0,0 +1,309 @@
+use claim::Claim;
+
+/// The area covered by two or more claims.
+///
+/// A vertical line sweeps over the left and right edges of the claims. In between two edges
+/// the length covered twice along the line doesn't change, that length is kept up to date by a
+/// segment tree over the (compressed) top and bottom edges. O(n log n), whatever the size of the
+/// fabric.
+pub fn overlap_area(claims: &[Claim]) -> i64 {
+    let claims: Vec<&Claim> = claims.iter()
+        .filter(|claim| !claim.is_empty())
+        .collect();
+
+    if claims.is_empty() {
+        return 0;
+    }
+
+    let ys = compress(claims.iter().flat_map(|claim| vec![claim.top(), claim.bottom()]));
+
+    // (x, coverage change, top, bottom)
+    let mut events: Vec<(i64, i32, usize, usize)> = claims.iter()
+        .flat_map(|claim| {
+            let top = index_of(&ys, claim.top());
+            let bottom = index_of(&ys, claim.bottom());
+
+            vec![(claim.left(), 1, top, bottom), (claim.right(), -1, top, bottom)]
+        })
+        .collect();
+
+    events.sort();
+
+    let mut tree = CoverTree::new(&ys);
+    let mut area = 0;
+    let mut prev_x = events[0].0;
+
+    for (x, delta, top, bottom) in events {
+        area += tree.covered_twice() * (x - prev_x);
+        tree.add(top, bottom, delta);
+        prev_x = x;
+    }
+    area
+}
+
+/// For every claim, the amount of other claims it overlaps with.
+///
+/// Instead of looking for overlaps, this counts the claims each claim is clear of: those
+/// completely to its left or right, plus those completely above or below it, minus those that
+/// are both (e.g. to the left and above), which are 2D dominance counts. O(n log n).
+pub fn overlap_counts(claims: &[Claim]) -> Vec<usize> {
+    let solid: Vec<&Claim> = claims.iter()
+        .filter(|claim| !claim.is_empty())
+        .collect();
+
+    let sorted = |key: fn(&Claim) -> i64| -> Vec<i64> {
+        let mut values: Vec<i64> = solid.iter().map(|claim| key(claim)).collect();
+        values.sort();
+        values
+    };
+    let (lefts, rights, tops, bottoms) = (sorted(Claim::left), sorted(Claim::right), sorted(Claim::top), sorted(Claim::bottom));
+
+    let at_most = |values: &Vec<i64>, max: i64| values.partition_point(|&v| v <= max);
+    let at_least = |values: &Vec<i64>, min: i64| values.len() - values.partition_point(|&v| v < min);
+
+    // points (a, b) of the other claims, and for every claim the corner (c, d) it should dominate
+    let dominated = |point: fn(&Claim) -> (i64, i64), corner: fn(&Claim) -> (i64, i64)| {
+        let points: Vec<(i64, i64)> = solid.iter().map(|claim| point(claim)).collect();
+        let corners: Vec<(i64, i64)> = solid.iter().map(|claim| corner(claim)).collect();
+
+        count_dominated(&points, &corners)
+    };
+
+    // left of and above, left of and below, right of and above, right of and below
+    let diagonal = [
+        dominated(|s| (s.right(), s.bottom()), |r| (r.left(), r.top())),
+        dominated(|s| (s.right(), -s.top()), |r| (r.left(), -r.bottom())),
+        dominated(|s| (-s.left(), s.bottom()), |r| (-r.right(), r.top())),
+        dominated(|s| (-s.left(), -s.top()), |r| (-r.right(), -r.bottom())),
+    ];
+
+    let mut solid_index = 0;
+
+    claims.iter()
+        .map(|claim| {
+            if claim.is_empty() {
+                return 0;
+            }
+            let i = solid_index;
+            solid_index += 1;
+
+            let clear_x = at_most(&rights, claim.left()) + at_least(&lefts, claim.right());
+            let clear_y = at_most(&bottoms, claim.top()) + at_least(&tops, claim.bottom());
+            let clear_both: usize = diagonal.iter().map(|counts| counts[i]).sum();
+
+            // a claim is never clear of itself
+            solid.len() - 1 - (clear_x + clear_y - clear_both)
+        })
+        .collect()
+}
+
+/// All claims that don't overlap with any other claim, in input order.
+pub fn non_overlapping(claims: &[Claim]) -> Vec<&Claim> {
+    claims.iter()
+        .zip(overlap_counts(claims))
+        .filter(|(_, count)| *count == 0)
+        .map(|(claim, _)| claim)
+        .collect()
+}
+
+/// For every corner, the amount of points that are at most the corner in both coordinates.
+fn count_dominated(points: &[(i64, i64)], corners: &[(i64, i64)]) -> Vec<usize> {
+    let ys = compress(points.iter().map(|point| point.1));
+
+    let mut points: Vec<(i64, i64)> = points.to_vec();
+    points.sort();
+
+    let mut queries: Vec<(i64, i64, usize)> = corners.iter()
+        .enumerate()
+        .map(|(i, corner)| (corner.0, corner.1, i))
+        .collect();
+    queries.sort();
+
+    let mut tree = FenwickTree::new(ys.len());
+    let mut counts = vec![0; corners.len()];
+    let mut next_point = 0;
+
+    for (x, y, i) in queries {
+        while next_point < points.len() && points[next_point].0 <= x {
+            tree.add(index_of(&ys, points[next_point].1));
+            next_point += 1;
+        }
+        counts[i] = tree.count_up_to(ys.partition_point(|&v| v <= y));
+    }
+    counts
+}
+
+/// Sorted, distinct values.
+fn compress<I: Iterator<Item = i64>>(values: I) -> Vec<i64> {
+    let mut values: Vec<i64> = values.collect();
+    values.sort();
+    values.dedup();
+    values
+}
+
+fn index_of(compressed: &[i64], value: i64) -> usize {
+    compressed.binary_search(&value)
+        .expect("Value should be part of the compressed coordinates")
+}
+
+/// Counts points per index, and how many there are up to an index.
+struct FenwickTree {
+    tree: Vec<usize>,
+}
+
+impl FenwickTree {
+    fn new(len: usize) -> FenwickTree {
+        FenwickTree { tree: vec![0; len + 1] }
+    }
+
+    fn add(&mut self, index: usize) {
+        let mut i = index + 1;
+        while i < self.tree.len() {
+            self.tree[i] += 1;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// The amount of points with an index below `end`.
+    fn count_up_to(&self, end: usize) -> usize {
+        let mut count = 0;
+        let mut i = end;
+        while i > 0 {
+            count += self.tree[i];
+            i -= i & i.wrapping_neg();
+        }
+        count
+    }
+}
+
+/// Segment tree over the segments between consecutive coordinates, tracking how much of them
+/// is covered at least once and at least twice.
+struct CoverTree<'a> {
+    coords: &'a [i64],
+    count: Vec<i32>,
+    once: Vec<i64>,
+    twice: Vec<i64>,
+}
+
+impl<'a> CoverTree<'a> {
+    fn new(coords: &'a [i64]) -> CoverTree<'a> {
+        let size = 4 * coords.len().max(1);
+        CoverTree { coords, count: vec![0; size], once: vec![0; size], twice: vec![0; size] }
+    }
+
+    fn covered_twice(&self) -> i64 {
+        self.twice[1]
+    }
+
+    /// Cover the coordinates from index `from` to index `to` `delta` more times.
+    fn add(&mut self, from: usize, to: usize, delta: i32) {
+        if from < to && self.coords.len() > 1 {
+            self.update(1, 0, self.coords.len() - 1, from, to, delta);
+        }
+    }
+
+    fn update(&mut self, node: usize, lo: usize, hi: usize, from: usize, to: usize, delta: i32) {
+        if to <= lo || hi <= from {
+            return;
+        }
+        if from <= lo && hi <= to {
+            self.count[node] += delta;
+        } else {
+            let mid = (lo + hi) / 2;
+            self.update(2 * node, lo, mid, from, to, delta);
+            self.update(2 * node + 1, mid, hi, from, to, delta);
+        }
+        self.recalculate(node, lo, hi);
+    }
+
+    fn recalculate(&mut self, node: usize, lo: usize, hi: usize) {
+        let full = self.coords[hi] - self.coords[lo];
+        let leaf = hi - lo == 1;
+
+        let (children_once, children_twice) = if leaf {
+            (0, 0)
+        } else {
+            (self.once[2 * node] + self.once[2 * node + 1], self.twice[2 * node] + self.twice[2 * node + 1])
+        };
+
+        match self.count[node] {
+            0   => {
+                self.once[node] = children_once;
+                self.twice[node] = children_twice;
+            }
+            1   => {
+                self.once[node] = full;
+                self.twice[node] = children_once;
+            }
+            _   => {
+                self.once[node] = full;
+                self.twice[node] = full;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims(lines: &[&str]) -> Vec<Claim> {
+        lines.iter().map(|line| Claim::from(line)).collect()
+    }
+
+    /// Compares every pair of claims.
+    fn overlap_counts_slow(claims: &[Claim]) -> Vec<usize> {
+        claims.iter()
+            .map(|r| {
+                claims.iter()
+                    .filter(|s| s.id != r.id && !s.is_empty() && !r.is_empty())
+                    .filter(|s| s.left() < r.right() && r.left() < s.right() && s.top() < r.bottom() && r.top() < s.bottom())
+                    .count()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_overlap_area() {
+        let example = claims(&["#1 @ 1,3: 4x4", "#2 @ 3,1: 4x4", "#3 @ 5,5: 2x2"]);
+        assert_eq!(overlap_area(&example), 4);
+
+        let nested = claims(&["#1 @ 0,0: 10x10", "#2 @ 2,2: 2x2", "#3 @ 3,3: 2x2", "#4 @ 20,20: 0x5"]);
+        assert_eq!(overlap_area(&nested), 7);
+
+        assert_eq!(overlap_area(&[]), 0);
+    }
+
+    #[test]
+    fn test_overlap_area_huge() {
+        let huge = claims(&["#1 @ 0,0: 3000000000x2000000000", "#2 @ 1000000000,-5: 4000000000x1000000005"]);
+
+        assert_eq!(overlap_area(&huge), 2_000_000_000 * 1_000_000_000);
+    }
+
+    #[test]
+    fn test_overlap_counts() {
+        let example = claims(&["#1 @ 1,3: 4x4", "#2 @ 3,1: 4x4", "#3 @ 5,5: 2x2"]);
+        assert_eq!(overlap_counts(&example), vec![1, 1, 0]);
+
+        // touching edges don't overlap, empty claims don't overlap
+        let touching = claims(&["#1 @ 0,0: 2x2", "#2 @ 2,0: 2x2", "#3 @ 0,2: 2x2", "#4 @ 1,1: 0x0", "#5 @ 1,1: 2x2"]);
+        assert_eq!(overlap_counts(&touching), vec![1, 1, 1, 0, 3]);
+    }
+
+    #[test]
+    fn test_overlap_counts_input() {
+        let input: Vec<&str> = include_str!("../input.txt").lines().collect();
+        let claims = Claim::from_vec(&input);
+
+        assert_eq!(overlap_counts(&claims), overlap_counts_slow(&claims));
+    }
+
+    #[test]
+    fn test_non_overlapping() {
+        let example = claims(&["#1 @ 1,3: 4x4", "#2 @ 3,1: 4x4", "#3 @ 5,5: 2x2", "#4 @ 100,100: 1x1"]);
+
+        let ids: Vec<i32> = non_overlapping(&example).iter().map(|claim| claim.id).collect();
+        assert_eq!(ids, vec![3, 4]);
+    }
+}
@@ -0,0 +1,253 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use shift::Shift;
+use std::collections::BTreeMap;
+
+/// The sleep of one guard during one night, minute by minute during the midnight hour. Sleep
+/// outside the midnight hour doesn't count, not in `minutes_asleep` either.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Night {
+    pub date: NaiveDate,
+    pub guard: i32,
+    pub asleep: [bool; 60],
+    pub minutes_asleep: u32,
+}
+
+impl Night {
+    pub fn from(shift: &Shift) -> Night {
+        let mut asleep = [false; 60];
+
+        for (start, end) in shift.naps() {
+            let mut minute = start;
+
+            while minute < end {
+                if minute.hour() == 0 {
+                    asleep[minute.minute() as usize] = true;
+                }
+                minute += Duration::minutes(1);
+            }
+        }
+
+        let minutes_asleep = asleep.iter().filter(|asleep| **asleep).count() as u32;
+
+        Night { date: shift.date(), guard: shift.guard, asleep, minutes_asleep }
+    }
+}
+
+/// Sleep statistics over all shifts.
+#[derive(Debug)]
+pub struct SleepLog {
+    nights: Vec<Night>,
+}
+
+impl SleepLog {
+    pub fn from(shifts: &[Shift]) -> SleepLog {
+        let mut nights: Vec<Night> = shifts.iter()
+            .map(Night::from)
+            .collect();
+
+        nights.sort_by_key(|night| (night.date, night.guard));
+
+        SleepLog { nights }
+    }
+
+    /// All guards, ordered by id.
+    pub fn guards(&self) -> Vec<i32> {
+        let mut guards: Vec<i32> = self.nights.iter()
+            .map(|night| night.guard)
+            .collect();
+
+        guards.sort();
+        guards.dedup();
+        guards
+    }
+
+    /// For every minute of the midnight hour, the amount of nights `guard` was asleep.
+    pub fn heatmap(&self, guard: i32) -> [u32; 60] {
+        let mut heatmap = [0; 60];
+
+        for night in self.nights.iter().filter(|night| night.guard == guard) {
+            for (minute, asleep) in night.asleep.iter().enumerate() {
+                if *asleep {
+                    heatmap[minute] += 1;
+                }
+            }
+        }
+        heatmap
+    }
+
+    /// The minute `guard` was most often asleep, and on how many nights. The earliest minute
+    /// wins a tie. None if the guard never slept.
+    pub fn sleepiest_minute(&self, guard: i32) -> Option<(u32, u32)> {
+        self.heatmap(guard).iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .max_by_key(|(minute, count)| (**count, std::cmp::Reverse(*minute)))
+            .map(|(minute, count)| (minute as u32, *count))
+    }
+
+    /// Total minutes asleep per guard.
+    pub fn total_per_guard(&self) -> BTreeMap<i32, u32> {
+        let mut totals = BTreeMap::new();
+
+        for night in &self.nights {
+            *totals.entry(night.guard).or_insert(0) += night.minutes_asleep;
+        }
+        totals
+    }
+
+    /// Total minutes asleep per date, of all guards on duty that night.
+    pub fn total_per_date(&self) -> BTreeMap<NaiveDate, u32> {
+        let mut totals = BTreeMap::new();
+
+        for night in &self.nights {
+            *totals.entry(night.date).or_insert(0) += night.minutes_asleep;
+        }
+        totals
+    }
+
+    /// The guard that slept the most minutes in total, the lowest id wins a tie.
+    pub fn sleepiest_guard(&self) -> Option<i32> {
+        self.total_per_guard().into_iter()
+            .max_by_key(|(guard, total)| (*total, std::cmp::Reverse(*guard)))
+            .map(|(guard, _)| guard)
+    }
+
+    /// The guard that was most often asleep on the same minute: (guard, minute, nights).
+    pub fn most_regular_sleeper(&self) -> Option<(i32, u32, u32)> {
+        self.guards().into_iter()
+            .filter_map(|guard| {
+                self.sleepiest_minute(guard)
+                    .map(|(minute, count)| (guard, minute, count))
+            })
+            .max_by_key(|(guard, minute, count)| (*count, std::cmp::Reverse((*guard, *minute))))
+    }
+
+    /// The night the guards slept the most, and how many minutes.
+    pub fn sleepiest_night(&self) -> Option<(NaiveDate, u32)> {
+        self.total_per_date().into_iter()
+            .max_by_key(|(date, total)| (*total, std::cmp::Reverse(*date)))
+    }
+
+    /// Every night as a row: the date, the guard, the minutes asleep and for every minute of the
+    /// midnight hour a 1 if the guard was asleep.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("date,guard,minutes_asleep");
+        for minute in 0..60 {
+            csv.push_str(&format!(",00:{:02}", minute));
+        }
+        csv.push('\n');
+
+        for night in &self.nights {
+            csv.push_str(&format!("{},{},{}", night.date, night.guard, night.minutes_asleep));
+            for asleep in night.asleep.iter() {
+                csv.push_str(if *asleep { ",1" } else { ",0" });
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use event::Event;
+
+    fn example() -> SleepLog {
+        let lines = vec![
+            "[1518-11-01 00:00] Guard #10 begins shift",
+            "[1518-11-01 00:05] falls asleep",
+            "[1518-11-01 00:25] wakes up",
+            "[1518-11-01 00:30] falls asleep",
+            "[1518-11-01 00:55] wakes up",
+            "[1518-11-01 23:58] Guard #99 begins shift",
+            "[1518-11-02 00:40] falls asleep",
+            "[1518-11-02 00:50] wakes up",
+            "[1518-11-03 00:05] Guard #10 begins shift",
+            "[1518-11-03 00:24] falls asleep",
+            "[1518-11-03 00:29] wakes up",
+            "[1518-11-04 00:02] Guard #99 begins shift",
+            "[1518-11-04 00:36] falls asleep",
+            "[1518-11-04 00:46] wakes up",
+            "[1518-11-05 00:03] Guard #99 begins shift",
+            "[1518-11-05 00:45] falls asleep",
+            "[1518-11-05 00:55] wakes up",
+        ];
+
+        SleepLog::from(&Shift::process_events(Event::from_vec(&lines).unwrap()).unwrap())
+    }
+
+    #[test]
+    fn test_nights() {
+        let log = example();
+
+        assert_eq!(log.nights.len(), 5);
+        assert_eq!(log.nights[1].date, NaiveDate::from_ymd(1518, 11, 2));
+        assert_eq!(log.nights[1].guard, 99);
+        assert_eq!(log.nights[1].minutes_asleep, 10);
+        assert!(log.nights[1].asleep[40]);
+        assert!(!log.nights[1].asleep[50]);
+    }
+
+    #[test]
+    fn test_night_across_midnight() {
+        let lines = vec![
+            "[1518-11-01 23:50] Guard #10 begins shift",
+            "[1518-11-01 23:55] falls asleep",
+            "[1518-11-02 00:05] wakes up",
+        ];
+        let log = SleepLog::from(&Shift::process_events(Event::from_vec(&lines).unwrap()).unwrap());
+
+        // the minutes before midnight are not part of the night
+        assert_eq!(log.nights[0].minutes_asleep, 5);
+        assert_eq!(log.total_per_guard().get(&10), Some(&5));
+        assert!(log.to_csv().lines().nth(1).unwrap().starts_with("1518-11-02,10,5,1,1,1,1,1,0,"));
+    }
+
+    #[test]
+    fn test_heatmap() {
+        let log = example();
+        let heatmap = log.heatmap(10);
+
+        assert_eq!(heatmap[4], 0);
+        assert_eq!(heatmap[5], 1);
+        assert_eq!(heatmap[24], 2);
+        assert_eq!(heatmap[25], 1);
+        assert_eq!(log.heatmap(42), [0; 60]);
+    }
+
+    #[test]
+    fn test_totals() {
+        let log = example();
+
+        assert_eq!(log.guards(), vec![10, 99]);
+        assert_eq!(log.total_per_guard().get(&10), Some(&50));
+        assert_eq!(log.total_per_guard().get(&99), Some(&30));
+        assert_eq!(log.total_per_date().get(&NaiveDate::from_ymd(1518, 11, 1)), Some(&45));
+        assert_eq!(log.sleepiest_guard(), Some(10));
+        assert_eq!(log.sleepiest_night(), Some((NaiveDate::from_ymd(1518, 11, 1), 45)));
+    }
+
+    #[test]
+    fn test_sleepiest_minute() {
+        let log = example();
+
+        assert_eq!(log.sleepiest_minute(10), Some((24, 2)));
+        assert_eq!(log.sleepiest_minute(99), Some((45, 3)));
+        assert_eq!(log.sleepiest_minute(42), None);
+        assert_eq!(log.most_regular_sleeper(), Some((99, 45, 3)));
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let csv = example().to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 6);
+        assert!(lines[0].starts_with("date,guard,minutes_asleep,00:00,00:01,"));
+        assert!(lines[0].ends_with(",00:59"));
+        assert!(lines[2].starts_with("1518-11-02,99,10,0,0,"));
+        assert_eq!(lines[2].split(',').count(), 63);
+    }
+}
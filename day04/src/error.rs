@@ -0,0 +1,47 @@
+use std::fmt;
+
+/// Something wrong in the log, `line` is the line number of the offending event (starting at 1).
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogError {
+    pub line: usize,
+    pub kind: ErrorKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ErrorKind {
+    /// The line isn't an event, contains the line itself.
+    Unparsable(String),
+    /// Someone falls asleep or wakes up before any guard began a shift.
+    NoGuardOnDuty,
+    /// The guard falls asleep while already asleep.
+    AlreadyAsleep,
+    /// The guard wakes up without having fallen asleep.
+    NotAsleep,
+}
+
+impl fmt::Display for LogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+
+        match self.kind {
+            ErrorKind::Unparsable(ref line)  => write!(f, "could not parse event `{}`", line),
+            ErrorKind::NoGuardOnDuty        => write!(f, "no guard has begun a shift yet"),
+            ErrorKind::AlreadyAsleep        => write!(f, "guard falls asleep while already asleep"),
+            ErrorKind::NotAsleep            => write!(f, "guard wakes up while not asleep"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let error = LogError { line: 3, kind: ErrorKind::NotAsleep };
+        assert_eq!(error.to_string(), "line 3: guard wakes up while not asleep");
+
+        let error = LogError { line: 7, kind: ErrorKind::Unparsable("[1518-13-01 00:00] wakes up".to_string()) };
+        assert_eq!(error.to_string(), "line 7: could not parse event `[1518-13-01 00:00] wakes up`");
+    }
+}
@@ -1,13 +1,16 @@
 use chrono::prelude::*;
+use error::{ErrorKind, LogError};
 use nom::types::CompleteStr;
 
 #[derive(Clone, Debug)]
 pub struct Event {
+    /// The line number in the log, starting at 1.
+    pub line: usize,
     pub datetime: NaiveDateTime,
     pub action: Action,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Action {
     BeginShiftOf(i32),
     WakesUp,
@@ -15,15 +18,20 @@ pub enum Action {
 }
 
 impl Event {
-    pub fn from(line: &str) -> Event {
-        parse_event(CompleteStr(line))
-            .expect("Failed to parse event")
-            .1
+    /// Parse the event on line number `line` of the log.
+    pub fn parse(line: usize, text: &str) -> Result<Event, LogError> {
+        match parse_event(CompleteStr(text.trim())) {
+            Ok((rest, event)) if rest.is_empty() => Ok(Event { line, ..event }),
+            _ => Err(LogError { line, kind: ErrorKind::Unparsable(text.to_string()) }),
+        }
     }
 
-    pub fn from_vec(lines: &Vec<&str>) -> Vec<Event> {
+    /// Parse all events of the log, skipping blank lines.
+    pub fn from_vec(lines: &[&str]) -> Result<Vec<Event>, LogError> {
         lines.iter()
-            .map(|line| Event::from(line))
+            .enumerate()
+            .filter(|(_, text)| !text.trim().is_empty())
+            .map(|(i, text)| Event::parse(i + 1, text))
             .collect()
     }
 }
@@ -48,6 +56,13 @@ named!(parse_u32<CompleteStr, u32>,
 
 // example: "[1518-11-01 00:00]"
 named!(parse_datetime<CompleteStr, NaiveDateTime>,
+    map_opt!(parse_datetime_fields, |(year, month, day, hour, minute)| {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .and_then(|date| date.and_hms_opt(hour, minute, 00))
+    })
+);
+
+named!(parse_datetime_fields<CompleteStr, (i32, u32, u32, u32, u32)>,
     do_parse!(
         tag!("[")               >>
         year: parse_i32         >>
@@ -61,7 +76,7 @@ named!(parse_datetime<CompleteStr, NaiveDateTime>,
         minute: parse_u32       >>
         tag!("]")               >>
 
-        (NaiveDate::from_ymd(year, month, day).and_hms(hour, minute, 00))
+        (year, month, day, hour, minute)
     )
 );
 
@@ -72,13 +87,14 @@ named!(parse_datetime<CompleteStr, NaiveDateTime>,
 named!(parse_action<CompleteStr, Action>,
     alt!(
         do_parse!(
-            tag!("Guard #") >>
-            id: parse_i32   >>
+            tag!("Guard #")         >>
+            id: parse_i32           >>
+            tag!(" begins shift")   >>
 
             (Action::BeginShiftOf(id))
-        )                                               |
-        value!(Action::FallsAsleep, tag!("falls"))      |
-        value!(Action::WakesUp, tag!("wakes"))
+        )                                                   |
+        value!(Action::FallsAsleep, tag!("falls asleep"))   |
+        value!(Action::WakesUp, tag!("wakes up"))
     )
 );
 
@@ -89,7 +105,7 @@ named!(parse_event<CompleteStr, Event>,
         take!(1)                    >>
         action: parse_action        >>
 
-        (Event { datetime, action })
+        (Event { line: 0, datetime, action })
     )
 );
 
@@ -134,20 +150,53 @@ mod tests {
     }
 
     #[test]
-    fn test_event_from() {
-        let event = Event::from("[1518-11-01 00:00] Guard #10 begins shift");
+    fn test_event_parse() {
+        let event = Event::parse(1, "[1518-11-01 00:00] Guard #10 begins shift").unwrap();
 
         assert_eq!(event.datetime, NaiveDate::from_ymd(1518, 11, 01).and_hms(00, 00, 00));
         assert_eq!(event.action, Action::BeginShiftOf(10));
 
-        let event = Event::from("[1518-11-01 00:05] falls asleep");
+        let event = Event::parse(2, "[1518-11-01 00:05] falls asleep").unwrap();
 
         assert_eq!(event.datetime, NaiveDate::from_ymd(1518, 11, 01).and_hms(00, 05, 00));
         assert_eq!(event.action, Action::FallsAsleep);
 
-        let event = Event::from("[1518-11-01 00:25] wakes up");
+        let event = Event::parse(3, "[1518-11-01 00:25] wakes up").unwrap();
 
+        assert_eq!(event.line, 3);
         assert_eq!(event.datetime, NaiveDate::from_ymd(1518, 11, 01).and_hms(00, 25, 00));
         assert_eq!(event.action, Action::WakesUp);
     }
+
+    #[test]
+    fn test_event_parse_invalid() {
+        for text in &["[1518-13-01 00:25] wakes up", "[1518-11-01 00:25] snores", "[1518-11-01 00:25]", "wakes up"] {
+            assert_eq!(
+                Event::parse(4, text).unwrap_err(),
+                LogError { line: 4, kind: ErrorKind::Unparsable(text.to_string()) }
+            );
+        }
+    }
+
+    #[test]
+    fn test_event_parse_partial_action() {
+        for text in &["[1518-11-01 00:25] falls off the wall", "[1518-11-01 00:25] wakes", "[1518-11-01 00:25] wakes up early", "[1518-11-01 00:00] Guard #10 quits"] {
+            assert_eq!(
+                Event::parse(5, text).unwrap_err(),
+                LogError { line: 5, kind: ErrorKind::Unparsable(text.to_string()) }
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_vec() {
+        let events = Event::from_vec(&["[1518-11-01 00:00] Guard #10 begins shift", "", "[1518-11-01 00:05] falls asleep"]).unwrap();
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].line, 3);
+
+        let error = Event::from_vec(&["[1518-11-01 00:00] Guard #10 begins shift", "", "garbage"]).unwrap_err();
+
+        assert_eq!(error.line, 3);
+    }
 }
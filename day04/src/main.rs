@@ -1,70 +1,103 @@
 extern crate chrono;
 #[macro_use]
 extern crate nom;
+extern crate structopt;
+extern crate util;
 
+mod analytics;
+mod error;
 mod event;
 mod shift;
 
+use analytics::SleepLog;
+use error::LogError;
 use event::*;
 use shift::*;
-use chrono::prelude::*;
+use std::path::Path;
+use structopt::StructOpt;
+use util::progress::Progress;
+use util::runner::{Day, RunnerOpt};
+
+#[derive(StructOpt, Debug)]
+struct Opt {
+    /// Print the minutes every guard was asleep, night by night, as CSV instead of solving the puzzles
+    #[structopt(long = "csv")]
+    csv: bool,
+
+    #[structopt(flatten)]
+    runner: RunnerOpt,
+}
 
-fn get_input() -> Vec<&'static str> {
-    include_str!("../input.txt")
-        .split("\n")
-        .filter(|s| s.len() > 0)
-        .collect()
+fn parse_input(input: &str) -> Vec<&str> {
+    // blank lines are kept, so errors can point to the right line
+    input.lines().collect()
 }
 
 fn main() {
-    let input = get_input();
+    let opt = Opt::from_args();
 
-    println!("Puzzle 1: {}", puzzle_1(&input));
-    println!("Puzzle 2: {}", puzzle_2(&input));
-}
+    if opt.csv {
+        let file = opt.runner.input
+            .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("input.txt"));
 
-fn puzzle_1(input: &Vec<&str>) -> i32 {
-    let events = Event::from_vec(&input);
-    let shifts = Shift::process_events(events);
+        let input = std::fs::read_to_string(&file)
+            .expect("Could not read input file");
 
-    let sleepy_guard = Shift::find_sleepy_guard(&shifts);
+        match sleep_log(&parse_input(&input)) {
+            Ok(log)     => print!("{}", log.to_csv()),
+            Err(error)  => {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
 
-    println!("The most sleepy guard is: {}", sleepy_guard);
+    Day::new(env!("CARGO_MANIFEST_DIR"), parse_input)
+        .try_puzzle_with_progress(puzzle_1)
+        .try_puzzle_with_progress(puzzle_2)
+        .run_with(opt.runner);
+}
 
-    let sleepy_shifts = Shift::filter_on_guard(shifts, sleepy_guard);
-    let sleepy_minute = Shift::find_sleepy_minute(&sleepy_shifts)
-        .expect("Could not find a sleepy minute")
-        .minute() as i32;
+fn sleep_log(input: &Vec<&str>) -> Result<SleepLog, LogError> {
+    let events = Event::from_vec(input)?;
+    let shifts = Shift::process_events(events)?;
 
-    println!("The most sleepy minute is: {}", sleepy_minute);
+    Ok(SleepLog::from(&shifts))
+}
 
-    sleepy_guard * sleepy_minute
+fn no_sleep() -> String {
+    "No guard ever fell asleep during the midnight hour".to_string()
 }
 
-fn puzzle_2(input: &Vec<&str>) -> i32 {
-    let events = Event::from_vec(&input);
-    let shifts = Shift::process_events(events);
+fn puzzle_1(input: &Vec<&str>, progress: &Progress) -> Result<i32, String> {
+    let log = sleep_log(input).map_err(|e| e.to_string())?;
+
+    if let Some((date, minutes)) = log.sleepiest_night() {
+        progress.log(&format!("The most sleepy night was {} with {} minutes", date, minutes));
+    }
 
-    let guards = Shift::get_guards(&shifts);
+    let sleepy_guard = log.sleepiest_guard()
+        .ok_or_else(no_sleep)?;
 
-    let (guard, time, count) = guards.iter()
-        .map(|guard| {
-            let shifts_guard = Shift::filter_on_guard(shifts.clone(), *guard);   // TODO this clone tho...
+    progress.log(&format!("The most sleepy guard is: {}", sleepy_guard));
 
-            if let Some((time, count)) = Shift::find_sleepy_minute_and_count(&shifts_guard) {
-                return Some((*guard, time, count));
-            } else {
-                return None;
-            }
-        })
-        .filter(Option::is_some)
-        .map(Option::unwrap)
-        .max_by_key(|(_, _, c)| *c)
-        .unwrap();
+    let (sleepy_minute, _) = log.sleepiest_minute(sleepy_guard)
+        .ok_or_else(no_sleep)?;
 
-    println!("guard: {:?}, time: {:?}, count: {:?}", guard, time, count);
+    progress.log(&format!("The most sleepy minute is: {}", sleepy_minute));
 
-    guard * (time.minute() as i32)
+    Ok(sleepy_guard * sleepy_minute as i32)
+}
+
+fn puzzle_2(input: &Vec<&str>, progress: &Progress) -> Result<i32, String> {
+    let (guard, minute, count) = sleep_log(input).map_err(|e| e.to_string())?
+        .most_regular_sleeper()
+        .ok_or_else(no_sleep)?;
+
+    progress.log(&format!("guard: {}, minute: {}, count: {}", guard, minute, count));
+
+    Ok(guard * minute as i32)
 }
 
 #[cfg(test)]
@@ -92,7 +125,7 @@ mod tests {
         example.push("[1518-11-05 00:45] falls asleep");
         example.push("[1518-11-05 00:55] wakes up");
 
-        assert_eq!(puzzle_1(&example), 240);
+        assert_eq!(puzzle_1(&example, &Progress::silent()), Ok(240));
     }
 
     #[test]
@@ -116,6 +149,15 @@ mod tests {
         example.push("[1518-11-05 00:45] falls asleep");
         example.push("[1518-11-05 00:55] wakes up");
 
-        assert_eq!(puzzle_2(&example), 4455);
+        assert_eq!(puzzle_2(&example, &Progress::silent()), Ok(4455));
+    }
+
+    #[test]
+    fn test_puzzle_errors() {
+        let example = vec!["[1518-11-01 00:00] Guard #10 begins shift", "", "[1518-11-01 00:25] wakes up"];
+        assert_eq!(puzzle_1(&example, &Progress::silent()), Err("line 3: guard wakes up while not asleep".to_string()));
+
+        let example = vec!["[1518-11-01 00:00] Guard #10 begins shift"];
+        assert_eq!(puzzle_2(&example, &Progress::silent()), Err(no_sleep()));
     }
 }
@@ -1,7 +1,7 @@
+use error::{ErrorKind, LogError};
 use event::*;
-use std::collections::HashMap;
-use chrono::Duration;
 use chrono::prelude::*;
+use std::collections::HashSet;
 
 #[derive(Clone, Debug)]
 pub struct Shift {
@@ -10,153 +10,181 @@ pub struct Shift {
 }
 
 impl Shift {
-    pub fn process_events(mut events: Vec<Event>) -> Vec<Shift> {
-        // sort chronological
-        events.sort_by(|e1, e2| { e1.datetime.cmp(&e2.datetime) });
+    /// Split the log into shifts. The events may be in any order and events that occur twice
+    /// are only counted once. Fails on the first event that doesn't fit in a shift.
+    pub fn process_events(mut events: Vec<Event>) -> Result<Vec<Shift>, LogError> {
+        // sort chronological, events at the same minute stay in the order of the log
+        events.sort_by_key(|event| event.datetime);
 
-        let mut shifts: Vec<Shift> = Vec::new();
+        let mut seen = HashSet::new();
+        events.retain(|event| seen.insert((event.datetime, event.action.clone())));
 
-        // parse into separate shifts, by taking everything up to the next BeginShiftOf
-        while events.len() > 0 {
-            match events.get(0).unwrap().action {
-                Action::BeginShiftOf(guard) => {
-                    let drain_index = Shift::index_of_second_begin_shift(&events)
-                        .unwrap_or(events.len());
+        let mut shifts: Vec<Shift> = Vec::new();
+        let mut asleep = false;
 
-                    let shift_events = events.drain(..drain_index).collect();
+        for event in events {
+            let error = |kind| LogError { line: event.line, kind };
 
-                    shifts.push(Shift { guard, events: shift_events });
-                }
-                _ => {
-                    panic!("BeginShiftOf not at begin of events")
+            match event.action {
+                Action::BeginShiftOf(guard) => {
+                    shifts.push(Shift { guard, events: Vec::new() });
+                    asleep = false;
+                },
+                Action::FallsAsleep         => {
+                    if shifts.is_empty() {
+                        return Err(error(ErrorKind::NoGuardOnDuty));
+                    }
+                    if asleep {
+                        return Err(error(ErrorKind::AlreadyAsleep));
+                    }
+                    asleep = true;
+                },
+                Action::WakesUp             => {
+                    if shifts.is_empty() {
+                        return Err(error(ErrorKind::NoGuardOnDuty));
+                    }
+                    if !asleep {
+                        return Err(error(ErrorKind::NotAsleep));
+                    }
+                    asleep = false;
                 }
             }
+
+            shifts.last_mut()
+                .expect("A shift has begun")
+                .events.push(event);
         }
-        shifts
+        Ok(shifts)
     }
 
-    fn index_of_second_begin_shift(events: &Vec<Event>) -> Option<usize> {
-        events.iter()
-            .enumerate()
-            .skip(1)
-            .find(|(_, e)| {
-                match e.action {
-                    Action::BeginShiftOf(_) => true,
-                    _ => false,
-                }
-            })
-            .map(|(i, _)| i)
+    /// The night this shift covers: a shift that begins before midnight counts for the next day.
+    pub fn date(&self) -> NaiveDate {
+        let begin = self.events[0].datetime;
+
+        if begin.hour() >= 12 {
+            begin.date().succ()
+        } else {
+            begin.date()
+        }
     }
 
-    /// Find the total amount of minutes the guard was asleep this shift.
-    pub fn total_time_asleep(&self) -> i32 {
-        let mut total_asleep = 0;
+    /// Every period the guard was asleep this shift, from falling asleep up to waking up. A guard
+    /// that is still asleep when the shift ends sleeps until the end of the midnight hour.
+    pub fn naps(&self) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+        let mut naps = Vec::new();
         let mut start_asleep: Option<NaiveDateTime> = None;
 
-        // events are expected to be sorted by date and time
+        // events are sorted and validated by process_events
         for event in &self.events {
             match event.action {
-                Action::BeginShiftOf(_)   => {},
+                Action::BeginShiftOf(_) => {},
                 Action::FallsAsleep     => {
                     start_asleep = Some(event.datetime);
                 },
                 Action::WakesUp         => {
-                    if let Some(start) = start_asleep {
-                        total_asleep += event.datetime.signed_duration_since(start).num_minutes();
-                    } else {
-                        panic!("A WakesUp occured before FallsAsleep");
+                    if let Some(start) = start_asleep.take() {
+                        naps.push((start, event.datetime));
                     }
                 }
             }
         }
-        total_asleep as i32
-    }
-
-    /// From a list of shifts, get all unique guards.
-    pub fn get_guards(shifts: &Vec<Shift>) -> Vec<i32> {
-        let mut guards: Vec<i32> = shifts.iter()
-            .map(|shift| shift.guard)
-            .collect();
 
-        guards.sort();
-        guards.dedup();
-
-        guards
-    }
+        if let Some(start) = start_asleep {
+            let end_of_hour = self.date().and_hms(1, 0, 0);
 
-    /// Filter a list of shifts on the specified guard.
-    pub fn filter_on_guard(shifts: Vec<Shift>, guard: i32) -> Vec<Shift> {
-        shifts.into_iter()
-            .filter(|shift| shift.guard == guard)
-            .collect()
+            naps.push((start, end_of_hour.max(start)));
+        }
+        naps
     }
+}
 
-    /// From a list of shifts, find the guard that slept the most in total.
-    pub fn find_sleepy_guard(shifts: &Vec<Shift>) -> i32 {
-        let mut sleep_time_per_guard: HashMap<i32, i32> = HashMap::new();
-
-        for shift in shifts {
-            *sleep_time_per_guard.entry(shift.guard).or_insert(0) += shift.total_time_asleep();
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        *sleep_time_per_guard.iter()
-            .max_by_key(|entry| entry.1)    // maximize on the value of the hashmap tuple
-            .unwrap()
-            .0
+    /// The total amount of minutes the guard was asleep during `shift`.
+    fn minutes_asleep(shift: &Shift) -> i64 {
+        shift.naps().iter()
+            .map(|(start, end)| end.signed_duration_since(*start).num_minutes())
+            .sum()
     }
 
-    /// From a list of shifts, find the minute a guard was most often asleep.
-    pub fn find_sleepy_minute(shifts: &Vec<Shift>) -> Option<NaiveTime> {
-        if let Some((time, _)) = Shift::find_sleepy_minute_and_count(shifts) {
-            return Some(time);
-        } else {
-            return None;
-        }
+    fn shifts(lines: &[&str]) -> Result<Vec<Shift>, LogError> {
+        Shift::process_events(Event::from_vec(lines).unwrap())
     }
 
-    /// From a list of shifts, find the minute a guard was most often asleep and how often that was.
-    pub fn find_sleepy_minute_and_count(shifts: &Vec<Shift>) -> Option<(NaiveTime, i32)> {
-        let mut time_asleep: HashMap<NaiveTime, i32> = HashMap::new();
-        let mut start_sleep: Option<NaiveDateTime> = None;
-
-        for shift in shifts {
-            // events are expected to be sorted by date and time
-            // also, we _assume_ each FallsAsleep is paired with a WakesUp
-            // and that a WakesUp never occurs before a FallsAsleep
-            for event in &shift.events {
-                match event.action {
-                    Action::BeginShiftOf(_)       => {},
-                    Action::FallsAsleep         => {
-                        start_sleep = Some(event.datetime);
-                    },
-                    Action::WakesUp             => {
-                        let mut start = start_sleep
-                            .expect("A WakesUp occured before FallsAsleep");
-                        let end = event.datetime;
-
-                        while start != end {
-                            let entry = time_asleep.entry(start.time()).or_insert(0);
-                            *entry += 1;
-
-                            start += Duration::minutes(1);
-                        }
-                    }
-                }
-            }
-        }
+    #[test]
+    fn test_naps() {
+        let shifts = shifts(&[
+            "[1518-11-01 23:58] Guard #99 begins shift",
+            "[1518-11-02 00:40] falls asleep",
+            "[1518-11-02 00:50] wakes up",
+            "[1518-11-02 00:52] falls asleep",
+            "[1518-11-02 00:55] wakes up",
+        ]).unwrap();
+
+        assert_eq!(shifts.len(), 1);
+        assert_eq!(shifts[0].date(), NaiveDate::from_ymd(1518, 11, 2));
+        assert_eq!(shifts[0].naps().len(), 2);
+        assert_eq!(minutes_asleep(&shifts[0]), 13);
+    }
 
-        let sleepy_minute = time_asleep.iter()
-            .max_by_key(|entry| entry.1);    // maximize on the value of the hashmap entry
+    #[test]
+    fn test_unsorted_and_duplicates() {
+        let shifts = shifts(&[
+            "[1518-11-02 00:40] falls asleep",
+            "[1518-11-03 00:05] Guard #10 begins shift",
+            "[1518-11-01 23:58] Guard #99 begins shift",
+            "[1518-11-02 00:50] wakes up",
+            "[1518-11-02 00:40] falls asleep",
+            "[1518-11-03 00:24] falls asleep",
+        ]).unwrap();
+
+        assert_eq!(shifts.len(), 2);
+        assert_eq!(shifts[0].guard, 99);
+        assert_eq!(shifts[0].date(), NaiveDate::from_ymd(1518, 11, 2));
+        assert_eq!(minutes_asleep(&shifts[0]), 10);
+
+        // guard #10 is still asleep at the end of the log
+        assert_eq!(shifts[1].guard, 10);
+        assert_eq!(shifts[1].naps(), vec![(NaiveDate::from_ymd(1518, 11, 3).and_hms(0, 24, 0), NaiveDate::from_ymd(1518, 11, 3).and_hms(1, 0, 0))]);
+        assert_eq!(minutes_asleep(&shifts[1]), 36);
+    }
 
-        if let Some((time, count)) = sleepy_minute {
-            return Some((time.clone(), *count));
-        } else {
-            return None;
-        }
+    #[test]
+    fn test_asleep_at_shift_change() {
+        let shifts = shifts(&[
+            "[1518-11-01 00:00] Guard #10 begins shift",
+            "[1518-11-01 00:30] falls asleep",
+            "[1518-11-01 23:58] Guard #99 begins shift",
+        ]).unwrap();
+
+        assert_eq!(minutes_asleep(&shifts[0]), 30);
+        assert_eq!(shifts[1].date(), NaiveDate::from_ymd(1518, 11, 2));
+        assert_eq!(minutes_asleep(&shifts[1]), 0);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    // the lack of tests makes me sad
+    #[test]
+    fn test_errors() {
+        let error = shifts(&[
+            "[1518-11-01 00:05] falls asleep",
+            "[1518-11-01 00:00] Guard #10 begins shift",
+            "[1518-11-01 00:25] wakes up",
+            "[1518-11-01 00:30] wakes up",
+        ]).unwrap_err();
+        assert_eq!(error, LogError { line: 4, kind: ErrorKind::NotAsleep });
+
+        let error = shifts(&[
+            "[1518-11-01 00:00] Guard #10 begins shift",
+            "[1518-11-01 00:05] falls asleep",
+            "[1518-11-01 00:10] falls asleep",
+        ]).unwrap_err();
+        assert_eq!(error, LogError { line: 3, kind: ErrorKind::AlreadyAsleep });
+
+        let error = shifts(&[
+            "[1518-11-01 00:05] falls asleep",
+            "[1518-11-01 00:10] Guard #10 begins shift",
+        ]).unwrap_err();
+        assert_eq!(error, LogError { line: 1, kind: ErrorKind::NoGuardOnDuty });
+    }
 }
@@ -1,77 +1,101 @@
-// Make sure to use 'cargo run --release` when using the complete input. This redcues execution
-// time from 23s to 0.5s...
-
-fn to_vec(input: &str) -> Vec<char> {
-    input.chars()
-        .collect()
+extern crate rayon;
+extern crate structopt;
+extern crate util;
+
+mod polymer;
+mod rules;
+
+use polymer::*;
+use rules::*;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+use util::runner::{Day, RunnerOpt};
+
+#[derive(StructOpt, Debug)]
+struct Opt {
+    /// Print every reaction, in order, instead of solving the puzzles
+    #[structopt(long = "trace")]
+    trace: bool,
+
+    /// Solve both puzzles while reading the input, without keeping it in memory, for inputs too
+    /// large to read at once
+    #[structopt(long = "stream")]
+    stream: bool,
+
+    /// React with the rules in this file instead of the puzzle's, one per line: `a B` or `Fe O -> FeO`
+    #[structopt(long = "rules", parse(from_os_str))]
+    rules: Option<PathBuf>,
+
+    #[structopt(flatten)]
+    runner: RunnerOpt,
 }
 
-/// Finds an active pair (= same type, but difference case) and returns the index.
-fn find_active_pair(polymer: &Vec<char>) -> Option<usize> {
-    polymer.iter()
-        .enumerate()
-        .find(|(i, c1)| {
-            if let Some(c2) = polymer.get(i + 1) {
-                return c1.eq_ignore_ascii_case(&c2) && *c1 != c2;
-            } else {
-                return false;
-            }
-        })
-        .map(|(i, _)| i)
-}
-
-/// Removes all active pairs from the polymer.
-fn let_it_react(mut polymer: Vec<char>) -> Vec<char> {
-    loop {
-        let active_pair = find_active_pair(&polymer);
-
-        if let Some(index) = active_pair {
-            // TODO: optimization:
-            //  - each remove means elements to the right have to be move one forward...
-            //  - use a linked list instead? remove multiple indexes at once?
-            polymer.remove(index);
-            polymer.remove(index);
-        } else {
-            break;
-        }
+fn main() {
+    let opt = Opt::from_args();
+
+    if opt.stream {
+        let file = opt.runner.input
+            .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("input.txt"));
+
+        let reduced = File::open(&file)
+            .and_then(reduce_reader)
+            .unwrap_or_else(|e| {
+                eprintln!("error: could not read {}: {}", file.display(), e);
+                std::process::exit(1);
+            });
+        let (removed, shortest) = shortest_without_one_type(&reduced)
+            .expect("There are 26 types to remove");
+
+        println!("Puzzle 1: {}", reduced.len());
+        println!("Puzzle 2: {} (without {})", shortest, removed as char);
+        return;
     }
-    polymer
-}
 
-fn main() {
-    let input = include_str!("../input.txt")
-        .trim();
+    if opt.trace || opt.rules.is_some() {
+        let file = opt.runner.input
+            .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("input.txt"));
 
-    println!("Puzzle 1: {}", puzzle_1(input));
-    println!("Puzzle 2: {}", puzzle_2(input));
-}
+        let input = std::fs::read_to_string(&file)
+            .expect("Could not read input file");
+
+        let rule: Box<dyn ReactionRule> = match opt.rules {
+            Some(rules) => {
+                let text = std::fs::read_to_string(&rules)
+                    .expect("Could not read rules file");
 
-fn puzzle_1(input: &str) -> i32 {
-    let mut polymer = to_vec(&input);
+                Box::new(RuleTable::parse(&text).unwrap_or_else(|e| panic!("{}", e)))
+            }
+            None        => Box::new(OppositePolarity),
+        };
 
-    polymer = let_it_react(polymer);
+        let reduction = react(&input, rule.as_ref(), opt.trace);
 
-    polymer.len() as i32
-}
+        for (i, step) in reduction.trace.iter().enumerate() {
+            let product = step.product.as_ref().map_or("nothing", |product| product.as_str());
+
+            println!("{}: {} {:?} + {} {:?} -> {}", i + 1, step.units.0, step.left, step.units.1, step.right, product);
+        }
+        println!("{} units left: {}", reduction.units.len(), reduction.units.concat());
+        return;
+    }
 
-fn puzzle_2(input: &str) -> i32 {
-    let mut polymer = to_vec(&input);
+    Day::new(env!("CARGO_MANIFEST_DIR"), |input| input.trim())
+        .puzzle(|input| puzzle_1(input))
+        .puzzle(|input| puzzle_2(input))
+        .run_with(opt.runner);
+}
 
-    // Thanks to Levi: adding this reduces execution time by 10...
-    polymer = let_it_react(polymer);
+fn puzzle_1(input: &str) -> usize {
+    reduce(input.bytes()).len()
+}
 
-    let filtered_polymers: Vec<Vec<char>> = "abcdefghijklmnopqrstuvwxyz".chars()
-        .map(|filtered_type| {
-            polymer.clone().iter()
-                .filter(|c| !c.eq_ignore_ascii_case(&filtered_type))
-                .map(|c| *c)
-                .collect()
-        })
-        .collect();
+fn puzzle_2(input: &str) -> usize {
+    // Thanks to Levi: reacting first makes every filtered polymer a lot shorter
+    let reduced = reduce(input.bytes());
 
-    filtered_polymers.iter()
-        .map(|polymer| let_it_react(polymer.clone()).len() as i32)
-        .min()
+    shortest_without_one_type(&reduced)
+        .map(|(_, len)| len)
         .expect("Could not find a solution")
 }
 
@@ -79,16 +103,6 @@ fn puzzle_2(input: &str) -> i32 {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_find_first_active_unit() {
-        assert_eq!(find_active_pair(&vec!['a', 'A', 'b', 'c']), Some(0));
-        assert_eq!(find_active_pair(&vec!['a', 'b', 'B', 'c']), Some(1));
-        assert_eq!(find_active_pair(&vec!['a', 'A', 'b', 'B']), Some(0));
-        assert_eq!(find_active_pair(&vec!['a', 'B', 'B', 'b']), Some(2));
-        assert_eq!(find_active_pair(&vec!['a', 'a', 'B', 'B']), None);
-        assert_eq!(find_active_pair(&vec!['a', 'b', 'c', 'D']), None);
-    }
-
     #[test]
     fn test_puzzle_1() {
         let example = "dabAcCaCBAcCcaDA";
@@ -0,0 +1,132 @@
+use rayon::prelude::*;
+use std::io::{self, BufReader, Read};
+
+/// Two units react if they are the same type (letter) but have an opposite polarity (case).
+pub fn reacts(a: u8, b: u8) -> bool {
+    a != b && a.eq_ignore_ascii_case(&b)
+}
+
+/// Let all units react, in a single pass. Every unit is pushed on a stack of units that didn't
+/// react (yet), unless it reacts with the unit on top, then both are gone. Only the stack is kept
+/// in memory, so `units` can be any byte stream, e.g. `reader.bytes()`. Whitespace is ignored.
+pub fn reduce<I>(units: I) -> Vec<u8>
+    where I: IntoIterator<Item = u8>
+{
+    let mut stack = Vec::new();
+
+    for unit in units {
+        if unit.is_ascii_whitespace() {
+            continue;
+        }
+
+        match stack.last() {
+            Some(&top) if reacts(top, unit) => {
+                stack.pop();
+            }
+            _ => stack.push(unit),
+        }
+    }
+    stack
+}
+
+/// Let all units read from `reader` react, without reading all of them into memory first. Stops
+/// at the first read error.
+pub fn reduce_reader<R>(reader: R) -> io::Result<Vec<u8>>
+    where R: Read
+{
+    let mut error = None;
+
+    let reduced = reduce(BufReader::new(reader).bytes().map_while(|byte| match byte {
+        Ok(byte)    => Some(byte),
+        Err(e)      => {
+            error = Some(e);
+            None
+        }
+    }));
+
+    match error {
+        Some(e) => Err(e),
+        None    => Ok(reduced),
+    }
+}
+
+/// The length of the shortest polymer after removing all units of one type and letting it react,
+/// and which type that is. Removing units doesn't make other units react that already reacted,
+/// so this starts from the `reduced` polymer. Every type is tried in parallel.
+pub fn shortest_without_one_type(reduced: &[u8]) -> Option<(u8, usize)> {
+    (0..26u8)
+        .into_par_iter()
+        .map(|i| b'a' + i)
+        .map(|removed| {
+            let units = reduced.iter()
+                .cloned()
+                .filter(|unit| unit.to_ascii_lowercase() != removed);
+
+            (removed, reduce(units).len())
+        })
+        .min_by_key(|(removed, len)| (*len, *removed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_reacts() {
+        assert!(reacts(b'a', b'A'));
+        assert!(reacts(b'B', b'b'));
+        assert!(!reacts(b'a', b'a'));
+        assert!(!reacts(b'a', b'B'));
+    }
+
+    #[test]
+    fn test_reduce() {
+        assert_eq!(reduce("aA".bytes()), b"");
+        assert_eq!(reduce("abBA".bytes()), b"");
+        assert_eq!(reduce("abAB".bytes()), b"abAB");
+        assert_eq!(reduce("aabAAB".bytes()), b"aabAAB");
+        assert_eq!(reduce("dabAcCaCBAcCcaDA\n".bytes()), b"dabCBAcaDA");
+    }
+
+    #[test]
+    fn test_reduce_stream() {
+        let stream = Cursor::new("dabAcCaCBAcCcaDA".repeat(1000))
+            .bytes()
+            .map(|byte| byte.unwrap());
+
+        assert_eq!(reduce(stream), "dabCBAcaDA".repeat(1000).into_bytes());
+    }
+
+    /// Returns `data`, then fails.
+    struct FailingReader<'a> {
+        data: &'a [u8],
+    }
+
+    impl<'a> Read for FailingReader<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.data.is_empty() {
+                return Err(io::Error::other("disk on fire"));
+            }
+            self.data.read(buf)
+        }
+    }
+
+    #[test]
+    fn test_reduce_reader() {
+        let input = "dabAcCaCBAcCcaDA\n".repeat(10_000);
+
+        assert_eq!(reduce_reader(input.as_bytes()).unwrap(), "dabCBAcaDA".repeat(10_000).into_bytes());
+
+        let error = reduce_reader(FailingReader { data: b"dabAcCaCBAcCcaDA" }).unwrap_err();
+        assert_eq!(error.to_string(), "disk on fire");
+    }
+
+    #[test]
+    fn test_shortest_without_one_type() {
+        let reduced = reduce("dabAcCaCBAcCcaDA".bytes());
+
+        assert_eq!(shortest_without_one_type(&reduced), Some((b'c', 4)));
+        assert_eq!(shortest_without_one_type(b""), Some((b'a', 0)));
+    }
+}
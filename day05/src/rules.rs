@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// What happens when two units react.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Reaction {
+    /// Both units are gone.
+    Annihilate,
+    /// Both units are replaced by a new unit, which might react again.
+    Produce(String),
+}
+
+/// Decides which units a polymer consists of and which of them react.
+pub trait ReactionRule {
+    /// Split a polymer into units, by default every character except whitespace is a unit.
+    fn units<'a>(&self, polymer: &'a str) -> Vec<&'a str> {
+        polymer.char_indices()
+            .filter(|(_, c)| !c.is_whitespace())
+            .map(|(i, c)| &polymer[i..i + c.len_utf8()])
+            .collect()
+    }
+
+    /// What happens when `left` is directly followed by `right`, None if they don't react.
+    fn react(&self, left: &str, right: &str) -> Option<Reaction>;
+}
+
+/// The rule of the puzzle: units of the same type but opposite polarity (case) annihilate.
+pub struct OppositePolarity;
+
+impl ReactionRule for OppositePolarity {
+    fn react(&self, left: &str, right: &str) -> Option<Reaction> {
+        if left != right && left.to_lowercase() == right.to_lowercase() {
+            Some(Reaction::Annihilate)
+        } else {
+            None
+        }
+    }
+}
+
+/// A table of reacting pairs, units can be longer than one character.
+#[derive(Debug, Default)]
+pub struct RuleTable {
+    reactions: HashMap<(String, String), Reaction>,
+    /// units of more than one character, longest first
+    long_units: Vec<String>,
+}
+
+impl RuleTable {
+    /// Parse one rule per line: `a B` if `a` followed by `B` annihilate, `Fe O -> FeO` if `Fe`
+    /// followed by `O` become `FeO`. Empty lines and lines starting with `#` are ignored.
+    pub fn parse(text: &str) -> Result<RuleTable, String> {
+        let mut table = RuleTable::default();
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let words: Vec<&str> = line.split_whitespace().collect();
+
+            match words.as_slice() {
+                [left, right]                   => table.add(left, right, Reaction::Annihilate),
+                [left, right, "->", product]    => table.add(left, right, Reaction::Produce(product.to_string())),
+                _                               => return Err(format!("line {}: expected `a b` or `a b -> c`, got `{}`", i + 1, line)),
+            }
+        }
+        Ok(table)
+    }
+
+    /// Let `left` followed by `right` react as `reaction`.
+    pub fn add(&mut self, left: &str, right: &str, reaction: Reaction) {
+        let product = match reaction {
+            Reaction::Produce(ref product)  => Some(product.clone()),
+            Reaction::Annihilate            => None,
+        };
+
+        for unit in [Some(left.to_string()), Some(right.to_string()), product].iter().flatten() {
+            if unit.chars().count() > 1 && !self.long_units.contains(unit) {
+                self.long_units.push(unit.clone());
+            }
+        }
+        self.long_units.sort_by_key(|unit| std::cmp::Reverse(unit.len()));
+
+        self.reactions.insert((left.to_string(), right.to_string()), reaction);
+    }
+}
+
+impl ReactionRule for RuleTable {
+    /// The longest unit of the table that matches, otherwise a single character.
+    fn units<'a>(&self, polymer: &'a str) -> Vec<&'a str> {
+        let mut units = Vec::new();
+        let mut rest = polymer;
+
+        while let Some(c) = rest.chars().next() {
+            let len = self.long_units.iter()
+                .find(|unit| rest.starts_with(unit.as_str()))
+                .map(|unit| unit.len())
+                .unwrap_or_else(|| c.len_utf8());
+
+            if !c.is_whitespace() {
+                units.push(&rest[..len]);
+            }
+            rest = &rest[len..];
+        }
+        units
+    }
+
+    fn react(&self, left: &str, right: &str) -> Option<Reaction> {
+        self.reactions.get(&(left.to_string(), right.to_string())).cloned()
+    }
+}
+
+/// One reaction. Units are numbered by their position in the original polymer, a produced unit
+/// spans the positions of the units it was made of.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Step {
+    pub left: Range<usize>,
+    pub right: Range<usize>,
+    pub units: (String, String),
+    pub product: Option<String>,
+}
+
+/// The units left after reacting, and the reactions in the order they happened if traced.
+#[derive(Debug)]
+pub struct Reduction {
+    pub units: Vec<String>,
+    pub trace: Vec<Step>,
+}
+
+/// Let the polymer react according to `rule`, from left to right: every unit reacts with the
+/// unit before it, a produced unit gets a chance to react with the unit before that.
+pub fn react<R>(polymer: &str, rule: &R, trace: bool) -> Reduction
+    where R: ReactionRule + ?Sized
+{
+    let mut stack: Vec<(String, Range<usize>)> = Vec::new();
+    let mut steps = Vec::new();
+
+    for (i, unit) in rule.units(polymer).into_iter().enumerate() {
+        let mut incoming = Some((unit.to_string(), i..i + 1));
+
+        while let Some((unit, span)) = incoming.take() {
+            let reaction = stack.last()
+                .and_then(|top| rule.react(&top.0, &unit));
+
+            let reaction = match reaction {
+                Some(reaction)  => reaction,
+                None            => {
+                    stack.push((unit, span));
+                    break;
+                }
+            };
+
+            let (top, top_span) = stack.pop().expect("The stack is not empty");
+            let product = match reaction {
+                Reaction::Produce(product)  => Some(product),
+                Reaction::Annihilate        => None,
+            };
+
+            if trace {
+                steps.push(Step {
+                    left: top_span.clone(),
+                    right: span.clone(),
+                    units: (top, unit),
+                    product: product.clone(),
+                });
+            }
+
+            incoming = product.map(|product| (product, top_span.start..span.end));
+        }
+    }
+
+    Reduction { units: stack.into_iter().map(|(unit, _)| unit).collect(), trace: steps }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polymer::reduce;
+
+    fn units(reduction: &Reduction) -> String {
+        reduction.units.concat()
+    }
+
+    #[test]
+    fn test_opposite_polarity() {
+        let reduction = react("dabAcCaCBAcCcaDA", &OppositePolarity, false);
+
+        assert_eq!(units(&reduction), "dabCBAcaDA");
+        assert!(reduction.trace.is_empty());
+    }
+
+    #[test]
+    fn test_opposite_polarity_input() {
+        let input = include_str!("../input.txt");
+
+        assert_eq!(react(input, &OppositePolarity, false).units.len(), reduce(input.bytes()).len());
+    }
+
+    #[test]
+    fn test_trace() {
+        let reduction = react("abBAc", &OppositePolarity, true);
+
+        assert_eq!(units(&reduction), "c");
+        assert_eq!(reduction.trace, vec![
+            Step { left: 1..2, right: 2..3, units: ("b".to_string(), "B".to_string()), product: None },
+            Step { left: 0..1, right: 3..4, units: ("a".to_string(), "A".to_string()), product: None },
+        ]);
+    }
+
+    #[test]
+    fn test_rule_table() {
+        let rules = RuleTable::parse("# pairs\na B\n\nx y -> z").unwrap();
+
+        assert_eq!(units(&react("aBaAxyc", &rules, false)), "aAzc");
+        assert_eq!(units(&react("Ba", &rules, false)), "Ba");
+
+        assert!(RuleTable::parse("a B C").is_err());
+    }
+
+    #[test]
+    fn test_produce_chain() {
+        // a produced unit reacts with the unit before it
+        let rules = RuleTable::parse("b c -> d\na d -> e\ne f").unwrap();
+        let reduction = react("abcf", &rules, true);
+
+        assert_eq!(units(&reduction), "");
+        assert_eq!(reduction.trace.len(), 3);
+        assert_eq!(reduction.trace[1], Step { left: 0..1, right: 1..3, units: ("a".to_string(), "d".to_string()), product: Some("e".to_string()) });
+        assert_eq!(reduction.trace[2].left, 0..3);
+        assert_eq!(reduction.trace[2].right, 3..4);
+    }
+
+    #[test]
+    fn test_long_units() {
+        let rules = RuleTable::parse("Fe O -> FeO\nFeO H2O").unwrap();
+
+        assert_eq!(rules.units("FeFeOH2O F"), vec!["Fe", "FeO", "H2O", "F"]);
+        assert_eq!(units(&react("Fe O H2O Fe", &rules, false)), "Fe");
+    }
+}
@@ -1,68 +1,50 @@
+mod metric;
 mod pos;
-mod loc;
-mod grid;
+mod safe_region;
+mod voronoi;
 
+use crate::metric::Metric;
 use crate::pos::Pos;
-use crate::loc::{Loc, State};
-use crate::grid::Grid;
+use crate::voronoi::Voronoi;
+use util::runner::Day;
 
-fn get_input() -> Vec<&'static str> {
-    include_str!("../input.txt")
-        .split("\n")
+fn parse_input(input: &str) -> Vec<&str> {
+    input.split("\n")
         .filter(|s| s.len() > 0)
         .collect()
 }
 
 fn main() {
-    let input = get_input();
+    Day::with_params(env!("CARGO_MANIFEST_DIR"), |input, params| {
+            let metric = metric::from_name(&params.require::<String>("metric"))
+                .unwrap_or_else(|e| panic!("{}", e));
 
-    println!("Puzzle 1: {}", puzzle_1(&input));
-    println!("Puzzle 2: {}", puzzle_2(&input, 10_000));
+            (parse_input(input), metric, params.require::<i32>("max_distance"))
+        })
+        .puzzle(|(input, metric, _)| puzzle_1(input, metric.as_ref()))
+        .puzzle(|(input, metric, max_dist)| puzzle_2(input, metric.as_ref(), *max_dist))
+        .run();
 }
 
-fn get_coords(input: &Vec<&str>) -> Vec<Loc> {
+fn get_coords(input: &Vec<&str>) -> Vec<Pos> {
     input.iter()
-        .enumerate()
-        .map(|(i, s)| Loc::new_coord(Pos::parse(s), i as i32))
+        .map(|s| Pos::parse(s))
         .collect()
 }
 
-fn puzzle_1(input: &Vec<&str>) -> i32 {
+fn puzzle_1(input: &Vec<&str>, metric: &dyn Metric) -> usize {
     let coords = get_coords(&input);
 
-    let grid = Grid::new_with(&coords);
-
-    coords.iter()
-        .filter_map(|loc| {
-            match loc.state {
-                State::Coord(id) => Some(id),
-                _ => None,
-            }
-        })
-        .map(|id| grid.get_locs_part_of(id))
-        .filter(|locs| {
-            locs.iter()
-                .find(|loc| loc.is_edge)
-                .is_none()
-        })
-        .map(|locs| locs.len())
-        .max()
-        .unwrap() as i32
+    Voronoi::new(&coords, metric)
+        .largest_finite_region()
+        .map(|(_, size)| size)
+        .expect("Every region is infinite")
 }
 
-fn puzzle_2(input: &Vec<&str>, max_dist: i32) -> i32 {
+fn puzzle_2(input: &Vec<&str>, metric: &dyn Metric, max_dist: i32) -> usize {
     let coords = get_coords(&input);
 
-    let grid = Grid::new_with(&coords);
-
-    grid.locations()
-        .map(|loc| {
-            coords.iter()
-                .map(|coord| loc.dist_to(&coord))
-                .sum()
-        })
-        .filter(|total_dist: &i32| *total_dist < max_dist)
-        .count() as i32
+    metric.safe_region_size(&coords, max_dist)
 }
 
 #[cfg(test)]
@@ -79,7 +61,7 @@ mod tests {
         example.push("5, 5");
         example.push("8, 9");
 
-        assert_eq!(puzzle_1(&example), 17);
+        assert_eq!(puzzle_1(&example, &metric::Manhattan), 17);
     }
 
     #[test]
@@ -92,6 +74,6 @@ mod tests {
         example.push("5, 5");
         example.push("8, 9");
 
-        assert_eq!(puzzle_2(&example, 32), 16);
+        assert_eq!(puzzle_2(&example, &metric::Manhattan, 32), 16);
     }
 }
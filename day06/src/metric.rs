@@ -0,0 +1,331 @@
+use crate::pos::Pos;
+use crate::safe_region;
+use std::fmt::Debug;
+
+/// How the distance between two positions is measured.
+pub trait Metric: Debug {
+    fn distance(&self, a: &Pos, b: &Pos) -> f64;
+
+    /// Steps to the neighbours of a position, such that the amount of steps between two positions
+    /// is their distance. None if there are no such steps.
+    fn steps(&self) -> Option<&'static [(i32, i32)]>;
+
+    /// The smallest and largest corner of an area that contains every finite region.
+    fn extent(&self, coords: &[Pos]) -> (Pos, Pos);
+
+    /// For every coordinate, whether infinitely many positions are closest to it.
+    fn infinite_regions(&self, coords: &[Pos]) -> Vec<bool>;
+
+    /// The amount of positions with a total distance to all coordinates below `max_dist`.
+    fn safe_region_size(&self, coords: &[Pos], max_dist: i32) -> usize {
+        safe_region::convex_region_size(coords, self, max_dist)
+    }
+}
+
+/// The metric with the name used in `params.toml`.
+pub fn from_name(name: &str) -> Result<Box<dyn Metric>, String> {
+    match name {
+        "manhattan" => Ok(Box::new(Manhattan)),
+        "chebyshev" => Ok(Box::new(Chebyshev)),
+        "euclidean" => Ok(Box::new(Euclidean)),
+        _           => Err(format!("Unknown metric `{}`, expected manhattan, chebyshev or euclidean", name)),
+    }
+}
+
+/// The coordinate closest to `pos`, None if several are equally close.
+pub fn nearest<M>(coords: &[Pos], metric: &M, pos: &Pos) -> Option<usize>
+    where M: Metric + ?Sized
+{
+    let mut nearest = None;
+    let mut min = f64::INFINITY;
+
+    for (id, coord) in coords.iter().enumerate() {
+        let distance = metric.distance(pos, coord);
+
+        if distance < min {
+            min = distance;
+            nearest = Some(id);
+        } else if distance == min {
+            nearest = None;
+        }
+    }
+    nearest
+}
+
+fn bounding_box(coords: &[Pos]) -> (Pos, Pos) {
+    let min = Pos::new(
+        coords.iter().map(|pos| pos.x).min().expect("No coordinates"),
+        coords.iter().map(|pos| pos.y).min().expect("No coordinates"),
+    );
+    let max = Pos::new(
+        coords.iter().map(|pos| pos.x).max().unwrap(),
+        coords.iter().map(|pos| pos.y).max().unwrap(),
+    );
+    (min, max)
+}
+
+/// Mark the regions the positions of `border` are part of as infinite.
+fn regions_at<I, M>(coords: &[Pos], metric: &M, border: I) -> Vec<bool>
+    where I: Iterator<Item = Pos>, M: Metric + ?Sized
+{
+    let mut infinite = vec![false; coords.len()];
+
+    for pos in border {
+        if let Some(id) = nearest(coords, metric, &pos) {
+            infinite[id] = true;
+        }
+    }
+    infinite
+}
+
+/// The distance along the grid lines: |dx| + |dy|.
+#[derive(Debug)]
+pub struct Manhattan;
+
+impl Metric for Manhattan {
+    fn distance(&self, a: &Pos, b: &Pos) -> f64 {
+        a.dist_to(b) as f64
+    }
+
+    fn steps(&self) -> Option<&'static [(i32, i32)]> {
+        Some(&[(1, 0), (-1, 0), (0, 1), (0, -1)])
+    }
+
+    /// Beyond the bounding box, moving away from it adds the same distance to every coordinate.
+    fn extent(&self, coords: &[Pos]) -> (Pos, Pos) {
+        bounding_box(coords)
+    }
+
+    /// For the same reason, a region is infinite exactly when it reaches the bounding box border.
+    fn infinite_regions(&self, coords: &[Pos]) -> Vec<bool> {
+        let (min, max) = bounding_box(coords);
+
+        let rows = (min.x..=max.x).flat_map(|x| vec![Pos::new(x, min.y), Pos::new(x, max.y)]);
+        let columns = (min.y..=max.y).flat_map(|y| vec![Pos::new(min.x, y), Pos::new(max.x, y)]);
+
+        regions_at(coords, self, rows.chain(columns))
+    }
+
+    fn safe_region_size(&self, coords: &[Pos], max_dist: i32) -> usize {
+        safe_region::separable_region_size(coords, max_dist)
+    }
+}
+
+/// The amount of king moves: max(|dx|, |dy|).
+#[derive(Debug)]
+pub struct Chebyshev;
+
+impl Chebyshev {
+    /// In the diagonal coordinates u = x + y and v = x - y, the Chebyshev distance is half the
+    /// Manhattan distance. Returns the bounding box of the coordinates in (u, v).
+    fn diagonal_box(coords: &[Pos]) -> (Pos, Pos) {
+        let diagonal: Vec<Pos> = coords.iter()
+            .map(|pos| Pos::new(pos.x + pos.y, pos.x - pos.y))
+            .collect();
+
+        bounding_box(&diagonal)
+    }
+}
+
+impl Metric for Chebyshev {
+    fn distance(&self, a: &Pos, b: &Pos) -> f64 {
+        (a.x - b.x).abs().max((a.y - b.y).abs()) as f64
+    }
+
+    fn steps(&self) -> Option<&'static [(i32, i32)]> {
+        Some(&[(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)])
+    }
+
+    /// The diagonal bounding box, which is a diamond in (x, y).
+    fn extent(&self, coords: &[Pos]) -> (Pos, Pos) {
+        let (min, max) = Chebyshev::diagonal_box(coords);
+
+        (
+            Pos::new((min.x + min.y).div_euclid(2), (min.x - max.y).div_euclid(2)),
+            Pos::new((max.x + max.y + 1).div_euclid(2), (max.x - min.y + 1).div_euclid(2)),
+        )
+    }
+
+    /// Moving diagonally away from the diagonal bounding box adds the same distance to every
+    /// coordinate, but only positions with u and v of the same parity exist: the border is two
+    /// positions thick.
+    fn infinite_regions(&self, coords: &[Pos]) -> Vec<bool> {
+        let (min, max) = Chebyshev::diagonal_box(coords);
+        let mut border = Vec::new();
+
+        for u in min.x - 1..=max.x + 1 {
+            let vs: Vec<i32> = if u <= min.x || u >= max.x {
+                (min.y - 1..=max.y + 1).collect()
+            } else {
+                vec![min.y - 1, min.y, max.y, max.y + 1]
+            };
+
+            for v in vs {
+                if (u - v) % 2 == 0 {
+                    border.push(Pos::new((u + v) / 2, (u - v) / 2));
+                }
+            }
+        }
+
+        regions_at(coords, self, border.into_iter())
+    }
+}
+
+/// The length of the straight line.
+#[derive(Debug)]
+pub struct Euclidean;
+
+impl Euclidean {
+    /// The center of the circle through three positions, None if they are on one line.
+    fn circumcenter(a: &Pos, b: &Pos, c: &Pos) -> Option<(f64, f64)> {
+        let (bx, by) = ((b.x - a.x) as f64, (b.y - a.y) as f64);
+        let (cx, cy) = ((c.x - a.x) as f64, (c.y - a.y) as f64);
+        let d = 2.0 * (bx * cy - by * cx);
+
+        if d == 0.0 {
+            return None;
+        }
+
+        let b2 = bx * bx + by * by;
+        let c2 = cx * cx + cy * cy;
+
+        Some((a.x as f64 + (cy * b2 - by * c2) / d, a.y as f64 + (bx * c2 - cx * b2) / d))
+    }
+}
+
+impl Metric for Euclidean {
+    fn distance(&self, a: &Pos, b: &Pos) -> f64 {
+        (((a.x - b.x) as f64).powi(2) + ((a.y - b.y) as f64).powi(2)).sqrt()
+    }
+
+    fn steps(&self) -> Option<&'static [(i32, i32)]> {
+        None
+    }
+
+    /// A finite region is a convex polygon, its corners are centers of circles through three
+    /// coordinates without any coordinate inside. Checks every three coordinates, O(n⁴).
+    fn extent(&self, coords: &[Pos]) -> (Pos, Pos) {
+        let (mut min, mut max) = bounding_box(coords);
+        let infinite = self.infinite_regions(coords);
+
+        for i in 0..coords.len() {
+            for j in i + 1..coords.len() {
+                for k in j + 1..coords.len() {
+                    if infinite[i] && infinite[j] && infinite[k] {
+                        continue;
+                    }
+                    let (x, y) = match Euclidean::circumcenter(&coords[i], &coords[j], &coords[k]) {
+                        Some(center)    => center,
+                        None            => continue,
+                    };
+
+                    let radius2 = (x - coords[i].x as f64).powi(2) + (y - coords[i].y as f64).powi(2);
+                    let empty = coords.iter()
+                        .all(|pos| (x - pos.x as f64).powi(2) + (y - pos.y as f64).powi(2) >= radius2 - 1e-6);
+
+                    if empty {
+                        min = Pos::new(min.x.min(x.floor() as i32), min.y.min(y.floor() as i32));
+                        max = Pos::new(max.x.max(x.ceil() as i32), max.y.max(y.ceil() as i32));
+                    }
+                }
+            }
+        }
+        (min, max)
+    }
+
+    /// A region is infinite exactly when its coordinate is on the border of the convex hull: there
+    /// is a line through it and another coordinate with all coordinates on one side.
+    fn infinite_regions(&self, coords: &[Pos]) -> Vec<bool> {
+        let cross = |o: &Pos, a: &Pos, b: &Pos| {
+            (a.x - o.x) as i64 * (b.y - o.y) as i64 - (a.y - o.y) as i64 * (b.x - o.x) as i64
+        };
+
+        coords.iter()
+            .map(|s| {
+                if coords.iter().filter(|pos| *pos == s).count() > 1 {
+                    return false;   // duplicates are never closest
+                }
+
+                let others: Vec<&Pos> = coords.iter().filter(|pos| *pos != s).collect();
+
+                others.is_empty() || others.iter().any(|t| {
+                    others.iter().all(|pos| cross(s, t, pos) >= 0)
+                        || others.iter().all(|pos| cross(s, t, pos) <= 0)
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance() {
+        let (a, b) = (Pos::new(1, 2), Pos::new(4, -2));
+
+        assert_eq!(Manhattan.distance(&a, &b), 7.0);
+        assert_eq!(Chebyshev.distance(&a, &b), 4.0);
+        assert_eq!(Euclidean.distance(&a, &b), 5.0);
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(format!("{:?}", from_name("chebyshev").unwrap()), "Chebyshev");
+        assert!(from_name("hamming").is_err());
+    }
+
+    #[test]
+    fn test_infinite_regions() {
+        // a plus, the center is enclosed for every metric
+        let plus = vec![Pos::new(0, -4), Pos::new(-4, 0), Pos::new(0, 0), Pos::new(4, 0), Pos::new(0, 4)];
+
+        assert_eq!(Manhattan.infinite_regions(&plus), vec![true, true, false, true, true]);
+        assert_eq!(Chebyshev.infinite_regions(&plus), vec![true, true, false, true, true]);
+        assert_eq!(Euclidean.infinite_regions(&plus), vec![true, true, false, true, true]);
+
+        // points on a line are all infinite for euclidean and manhattan, but for chebyshev the
+        // middle one ties with the others far away
+        let line = vec![Pos::new(0, 0), Pos::new(3, 0), Pos::new(6, 0)];
+
+        assert_eq!(Euclidean.infinite_regions(&line), vec![true, true, true]);
+        assert_eq!(Manhattan.infinite_regions(&line), vec![true, true, true]);
+        assert_eq!(Chebyshev.infinite_regions(&line), vec![true, false, true]);
+    }
+
+    #[test]
+    fn test_euclidean_hull() {
+        let square = vec![Pos::new(0, 0), Pos::new(10, 0), Pos::new(0, 10), Pos::new(10, 10), Pos::new(5, 5), Pos::new(5, 0), Pos::new(5, 5)];
+
+        assert_eq!(Euclidean.infinite_regions(&square), vec![true, true, true, true, false, true, false]);
+    }
+
+    #[test]
+    fn test_extent() {
+        let coords = vec![Pos::new(0, 0), Pos::new(4, 2)];
+
+        assert_eq!(Manhattan.extent(&coords), (Pos::new(0, 0), Pos::new(4, 2)));
+        assert_eq!(Chebyshev.extent(&coords), (Pos::new(0, -1), Pos::new(4, 3)));
+    }
+
+    /// Positions far away from the coordinates are closest to an infinite region.
+    #[test]
+    fn test_far_away() {
+        let coords: Vec<Pos> = include_str!("../input.txt").lines().map(Pos::parse).collect();
+        let metrics: Vec<Box<dyn Metric>> = vec![Box::new(Manhattan), Box::new(Chebyshev), Box::new(Euclidean)];
+
+        for metric in metrics {
+            let infinite = metric.infinite_regions(&coords);
+
+            for i in 0..360 {
+                let angle = (i as f64).to_radians();
+                let pos = Pos::new((100_000.0 * angle.cos()) as i32, (100_000.0 * angle.sin()) as i32);
+
+                if let Some(id) = nearest(&coords, metric.as_ref(), &pos) {
+                    assert!(infinite[id], "{:?}: {:?} is closest to {}", metric, pos, id);
+                }
+            }
+        }
+    }
+}
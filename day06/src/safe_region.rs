@@ -0,0 +1,219 @@
+use crate::metric::Metric;
+use crate::pos::Pos;
+
+/// The amount of positions with a total Manhattan distance to all coordinates below `max_dist`.
+///
+/// The total Manhattan distance is the sum of the total distance along x and the total distance
+/// along y. Both are computed for every x and y that could be part of the region, after sorting
+/// them the pairs below `max_dist` are counted with two pointers: one going up through the x's
+/// while the other goes down through the y's. O(n log n + r log r), r the width of the region.
+pub fn separable_region_size(coords: &[Pos], max_dist: i32) -> usize {
+    if coords.is_empty() || max_dist <= 0 {
+        return 0;
+    }
+
+    let max_dist = max_dist as i64;
+    let mut xs = profile(coords.iter().map(|pos| pos.x).collect(), max_dist);
+    let mut ys = profile(coords.iter().map(|pos| pos.y).collect(), max_dist);
+
+    xs.sort();
+    ys.sort();
+
+    let mut size = 0;
+    let mut below = ys.len();     // the amount of y's that still fit with the current x
+
+    for x in xs {
+        while below > 0 && x + ys[below - 1] >= max_dist {
+            below -= 1;
+        }
+        size += below;
+    }
+    size
+}
+
+/// The total distance to `values` of every position along one axis where it is below `max_dist`.
+fn profile(mut values: Vec<i32>, max_dist: i64) -> Vec<i64> {
+    values.sort();
+
+    let n = values.len() as i64;
+    // outside the values, every step away adds n to the total
+    let margin = max_dist / n + 1;
+    let first = values[0] as i64 - margin;
+    let last = values[values.len() - 1] as i64 + margin;
+
+    let mut total: i64 = values.iter().map(|v| *v as i64 - first).sum();
+    let mut passed = 0;     // the amount of values at or before the position
+    let mut profile = Vec::new();
+
+    for pos in first..=last {
+        while passed < values.len() && values[passed] as i64 <= pos {
+            passed += 1;
+        }
+        if total < max_dist {
+            profile.push(total);
+        }
+        // one step further, the passed values get further away and the others closer
+        total += passed as i64 - (n - passed as i64);
+    }
+    profile
+}
+
+/// The amount of positions with a total distance to all coordinates below `max_dist`.
+///
+/// The total distance is a sum of norms, so it is convex: along a row it goes down up to its
+/// minimum and up after that, the positions in the region form one range. The same holds for the
+/// minimum of every row. Minima are found with a binary search on the slope and the ends of the
+/// ranges with a binary search as well, so this is exact however far the region reaches.
+pub fn convex_region_size<M>(coords: &[Pos], metric: &M, max_dist: i32) -> usize
+    where M: Metric + ?Sized
+{
+    if coords.is_empty() || max_dist <= 0 {
+        return 0;
+    }
+
+    let max_dist = max_dist as f64;
+    let total = |x: i32, y: i32| -> f64 {
+        let pos = Pos::new(x, y);
+        coords.iter().map(|coord| metric.distance(&pos, coord)).sum()
+    };
+
+    // every metric is at least as large as |dx| and |dy|: outside these bounds one coordinate
+    // is already too far away
+    let margin = max_dist as i32;
+    let (min_x, max_x) = (coords.iter().map(|pos| pos.x).min().unwrap() - margin, coords.iter().map(|pos| pos.x).max().unwrap() + margin);
+    let (min_y, max_y) = (coords.iter().map(|pos| pos.y).min().unwrap() - margin, coords.iter().map(|pos| pos.y).max().unwrap() + margin);
+
+    let best_x = |y: i32| lowest(min_x, max_x, |x| total(x, y));
+    let row_min = |y: i32| total(best_x(y), y);
+
+    let row_size = |y: i32| -> usize {
+        let best = best_x(y);
+        if total(best, y) >= max_dist {
+            return 0;
+        }
+
+        let left = first(min_x, best, |x| total(x, y) < max_dist);
+        let right = first(best, max_x + 1, |x| total(x, y) >= max_dist);
+
+        (right - left) as usize
+    };
+
+    let best_y = lowest(min_y, max_y, row_min);
+    let mut size = 0;
+
+    let mut y = best_y;
+    while y <= max_y && row_min(y) < max_dist {
+        size += row_size(y);
+        y += 1;
+    }
+
+    let mut y = best_y - 1;
+    while y >= min_y && row_min(y) < max_dist {
+        size += row_size(y);
+        y -= 1;
+    }
+
+    size
+}
+
+/// Where convex `f` is lowest in `[lo, hi]`: the first position after which it stops going down.
+fn lowest<F>(lo: i32, hi: i32, f: F) -> i32
+    where F: Fn(i32) -> f64
+{
+    first(lo, hi, |x| f(x + 1) >= f(x))
+}
+
+/// The first position in `[lo, hi)` where `pred` holds, `hi` if none. `pred` has to be false up to
+/// some position and true after it.
+fn first<P>(mut lo: i32, mut hi: i32, pred: P) -> i32
+    where P: Fn(i32) -> bool
+{
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+
+        if pred(mid) {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    lo
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::{Chebyshev, Euclidean, Manhattan};
+
+    fn example() -> Vec<Pos> {
+        ["1, 1", "1, 6", "8, 3", "3, 4", "5, 5", "8, 9"].iter()
+            .map(|s| Pos::parse(s))
+            .collect()
+    }
+
+    /// Checks every position that could be part of the region.
+    fn safe_region_size_slow(coords: &[Pos], metric: &dyn Metric, max_dist: i32) -> usize {
+        let mut size = 0;
+
+        for x in -max_dist..=max_dist + 10 {
+            for y in -max_dist..=max_dist + 10 {
+                let pos = Pos::new(x, y);
+                let total: f64 = coords.iter().map(|coord| metric.distance(&pos, coord)).sum();
+
+                if total < max_dist as f64 {
+                    size += 1;
+                }
+            }
+        }
+        size
+    }
+
+    #[test]
+    fn test_example() {
+        assert_eq!(convex_region_size(&example(), &Manhattan, 32), 16);
+        assert_eq!(convex_region_size(&example(), &Manhattan, 0), 0);
+        assert_eq!(separable_region_size(&example(), 32), 16);
+        assert_eq!(separable_region_size(&example(), 0), 0);
+    }
+
+    #[test]
+    fn test_profile() {
+        // 1, 1 and 4: the totals at -1 ..= 5 are 9, 6, 3, 4, 5, 6, 9
+        assert_eq!(profile(vec![4, 1, 1], 8), vec![6, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_separable() {
+        let coords: Vec<Pos> = include_str!("../input.txt").lines().map(Pos::parse).collect();
+
+        for max_dist in &[1, 5_000, 10_000, 25_000, 100_000, 1_000_000] {
+            assert_eq!(
+                separable_region_size(&coords, *max_dist),
+                convex_region_size(&coords, &Manhattan, *max_dist),
+                "{}", max_dist
+            );
+        }
+    }
+
+    #[test]
+    fn test_separable_far_beyond_bounding_box() {
+        // one coordinate: a diamond with radius r - 1 has 2r² - 2r + 1 positions
+        assert_eq!(separable_region_size(&[Pos::new(0, 0)], 1_000), 2 * 1_000 * 1_000 - 2 * 1_000 + 1);
+        assert_eq!(separable_region_size(&[Pos::new(3, 3), Pos::new(3, 3)], 4), 5);
+    }
+
+    #[test]
+    fn test_beyond_bounding_box() {
+        let metrics: Vec<Box<dyn Metric>> = vec![Box::new(Manhattan), Box::new(Chebyshev), Box::new(Euclidean)];
+
+        for metric in metrics {
+            for max_dist in &[10, 32, 100, 200] {
+                assert_eq!(
+                    convex_region_size(&example(), metric.as_ref(), *max_dist),
+                    safe_region_size_slow(&example(), metric.as_ref(), *max_dist),
+                    "{:?} {}", metric, max_dist
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use crate::metric::{self, Metric};
+use crate::pos::Pos;
+use std::collections::VecDeque;
+
+/// What a cell is closest to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Cell {
+    Unvisited,
+    /// The index of the closest coordinate.
+    Nearest(usize),
+    /// Two or more coordinates are equally close.
+    Tie,
+}
+
+/// Every cell of the area that contains all finite regions (see `Metric::extent`), labeled with
+/// the coordinate closest to it.
+pub struct Voronoi {
+    min: Pos,
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    infinite: Vec<bool>,
+}
+
+impl Voronoi {
+    /// If the metric has steps, a breadth-first search starting from all coordinates at once. A
+    /// cell is reached first by the coordinates closest to it, through its neighbours one step
+    /// closer: if those don't agree on a single coordinate, the cell is a tie. Every cell is visited
+    /// once, O(cells + coords). Otherwise every cell is compared with every coordinate.
+    pub fn new<M>(coords: &[Pos], metric: &M) -> Voronoi
+        where M: Metric + ?Sized
+    {
+        let (min, max) = metric.extent(coords);
+
+        let mut voronoi = Voronoi {
+            width: (max.x - min.x + 1) as usize,
+            height: (max.y - min.y + 1) as usize,
+            cells: Vec::new(),
+            infinite: metric.infinite_regions(coords),
+            min,
+        };
+        voronoi.cells = vec![Cell::Unvisited; voronoi.width * voronoi.height];
+
+        match metric.steps() {
+            Some(steps) => voronoi.search(coords, steps),
+            None        => {
+                for i in 0..voronoi.cells.len() {
+                    let pos = voronoi.pos_of(i);
+
+                    voronoi.cells[i] = metric::nearest(coords, metric, &pos)
+                        .map_or(Cell::Tie, Cell::Nearest);
+                }
+            }
+        }
+
+        voronoi
+    }
+
+    fn search(&mut self, coords: &[Pos], steps: &[(i32, i32)]) {
+        let mut distance = vec![usize::MAX; self.cells.len()];
+        let mut queue = VecDeque::new();
+
+        for (id, pos) in coords.iter().enumerate() {
+            let i = self.index_of(pos).expect("Coordinates are part of the extent");
+
+            self.cells[i] = match self.cells[i] {
+                Cell::Unvisited => Cell::Nearest(id),
+                _               => Cell::Tie,     // the same coordinate appears twice
+            };
+            if distance[i] != 0 {
+                distance[i] = 0;
+                queue.push_back(i);
+            }
+        }
+
+        while let Some(i) = queue.pop_front() {
+            let cell = self.cells[i];
+            let pos = self.pos_of(i);
+
+            for (dx, dy) in steps {
+                let n = match self.index_of(&Pos::new(pos.x + dx, pos.y + dy)) {
+                    Some(n) => n,
+                    None    => continue,
+                };
+
+                if distance[n] == usize::MAX {
+                    distance[n] = distance[i] + 1;
+                    self.cells[n] = cell;
+                    queue.push_back(n);
+                } else if distance[n] == distance[i] + 1 && self.cells[n] != cell {
+                    self.cells[n] = Cell::Tie;
+                }
+            }
+        }
+    }
+
+    fn pos_of(&self, i: usize) -> Pos {
+        Pos::new(self.min.x + (i % self.width) as i32, self.min.y + (i / self.width) as i32)
+    }
+
+    fn index_of(&self, pos: &Pos) -> Option<usize> {
+        let x = pos.x - self.min.x;
+        let y = pos.y - self.min.y;
+
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+        Some(y as usize * self.width + x as usize)
+    }
+
+    /// For every coordinate the size of its region, None if the region is infinite.
+    pub fn region_sizes(&self) -> Vec<Option<usize>> {
+        let mut sizes = vec![0; self.infinite.len()];
+
+        for cell in &self.cells {
+            if let Cell::Nearest(id) = cell {
+                sizes[*id] += 1;
+            }
+        }
+
+        sizes.into_iter()
+            .zip(&self.infinite)
+            .map(|(size, infinite)| if *infinite { None } else { Some(size) })
+            .collect()
+    }
+
+    /// The coordinate with the largest finite region and its size.
+    pub fn largest_finite_region(&self) -> Option<(usize, usize)> {
+        self.region_sizes().into_iter()
+            .enumerate()
+            .filter_map(|(id, size)| size.map(|size| (id, size)))
+            .max_by_key(|(id, size)| (*size, std::cmp::Reverse(*id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::{Chebyshev, Euclidean, Manhattan};
+
+    /// The label of `pos`, None outside the extent.
+    fn cell_at(voronoi: &Voronoi, pos: &Pos) -> Option<Cell> {
+        voronoi.index_of(pos).map(|i| voronoi.cells[i])
+    }
+
+    /// All positions of the extent with their label.
+    fn cells(voronoi: &Voronoi) -> Vec<(Pos, Cell)> {
+        voronoi.cells.iter()
+            .enumerate()
+            .map(|(i, cell)| (voronoi.pos_of(i), *cell))
+            .collect()
+    }
+
+    fn example() -> Vec<Pos> {
+        ["1, 1", "1, 6", "8, 3", "3, 4", "5, 5", "8, 9"].iter()
+            .map(|s| Pos::parse(s))
+            .collect()
+    }
+
+    fn metrics() -> Vec<Box<dyn Metric>> {
+        vec![Box::new(Manhattan), Box::new(Chebyshev), Box::new(Euclidean)]
+    }
+
+    /// Compares every cell with every coordinate.
+    fn nearest_slow(coords: &[Pos], metric: &dyn Metric, pos: &Pos) -> Cell {
+        metric::nearest(coords, metric, pos).map_or(Cell::Tie, Cell::Nearest)
+    }
+
+    #[test]
+    fn test_cells() {
+        let coords = example();
+        let voronoi = Voronoi::new(&coords, &Manhattan);
+
+        assert_eq!(cell_at(&voronoi, &Pos::new(1, 1)), Some(Cell::Nearest(0)));
+        assert_eq!(cell_at(&voronoi, &Pos::new(5, 2)), Some(Cell::Nearest(4)));
+        assert_eq!(cell_at(&voronoi, &Pos::new(5, 1)), Some(Cell::Tie));
+        assert_eq!(cell_at(&voronoi, &Pos::new(0, 0)), None);
+
+        for metric in metrics() {
+            let voronoi = Voronoi::new(&coords, metric.as_ref());
+
+            for (pos, cell) in cells(&voronoi) {
+                assert_eq!(cell, nearest_slow(&coords, metric.as_ref(), &pos), "{:?} {:?}", metric, pos);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cells_input() {
+        let coords: Vec<Pos> = include_str!("../input.txt").lines().map(Pos::parse).collect();
+
+        for metric in metrics() {
+            let voronoi = Voronoi::new(&coords, metric.as_ref());
+
+            for (pos, cell) in cells(&voronoi).into_iter().step_by(97) {
+                assert_eq!(cell, nearest_slow(&coords, metric.as_ref(), &pos), "{:?} {:?}", metric, pos);
+            }
+        }
+    }
+
+    /// Finite regions don't reach beyond the extent, counting them in a larger area gives the
+    /// same sizes.
+    #[test]
+    fn test_region_sizes_extent() {
+        let coords = vec![Pos::new(0, 0), Pos::new(9, 1), Pos::new(4, 3), Pos::new(5, 4), Pos::new(1, 8), Pos::new(8, 9), Pos::new(4, 12)];
+
+        for metric in metrics() {
+            let voronoi = Voronoi::new(&coords, metric.as_ref());
+            let mut sizes = vec![0; coords.len()];
+
+            for x in -30..40 {
+                for y in -30..40 {
+                    if let Some(id) = metric::nearest(&coords, metric.as_ref(), &Pos::new(x, y)) {
+                        sizes[id] += 1;
+                    }
+                }
+            }
+
+            for (id, size) in voronoi.region_sizes().into_iter().enumerate() {
+                if let Some(size) = size {
+                    assert_eq!(size, sizes[id], "{:?} region {}", metric, id);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_region_sizes() {
+        let voronoi = Voronoi::new(&example(), &Manhattan);
+
+        assert_eq!(voronoi.region_sizes(), vec![None, None, None, Some(9), Some(17), None]);
+        assert_eq!(voronoi.largest_finite_region(), Some((4, 17)));
+    }
+
+    #[test]
+    fn test_infinite_on_max_border() {
+        // the last row and column are part of the border as well
+        let coords = vec![Pos::new(0, 0), Pos::new(4, 0), Pos::new(4, 4), Pos::new(0, 4), Pos::new(2, 2)];
+        let voronoi = Voronoi::new(&coords, &Manhattan);
+
+        assert_eq!(voronoi.region_sizes()[2], None);
+        assert_eq!(voronoi.region_sizes()[4], Some(5));
+    }
+
+    #[test]
+    fn test_duplicate_coordinates() {
+        let coords = vec![Pos::new(0, 0), Pos::new(2, 2), Pos::new(2, 2), Pos::new(4, 4)];
+        let voronoi = Voronoi::new(&coords, &Manhattan);
+
+        assert_eq!(cell_at(&voronoi, &Pos::new(2, 2)), Some(Cell::Tie));
+        assert_eq!(voronoi.region_sizes()[1], Some(0));
+    }
+}
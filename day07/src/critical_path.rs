@@ -0,0 +1,198 @@
+use crate::graph::{Cycle, Graph};
+use crate::schedule::schedule;
+use std::collections::HashMap;
+use std::fmt;
+
+/// When a step can be done without delaying the whole, with unlimited workers.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Timing {
+    pub step: char,
+    pub duration: i32,
+    /// as soon as all steps it depends on are done
+    pub earliest_start: i32,
+    /// as late as possible without finishing later than the critical path
+    pub latest_start: i32,
+    /// how long the step can be delayed, 0 for steps on a critical path
+    pub slack: i32,
+}
+
+/// The longest chain of steps that depend on each other, it decides how long the steps take with
+/// unlimited workers.
+#[derive(Debug)]
+pub struct CriticalPath {
+    /// in topological order
+    pub timings: Vec<Timing>,
+    pub duration: i32,
+    /// the critical steps in the order they are done, the first alphabetically where it splits
+    pub chain: Vec<char>,
+}
+
+/// The critical path method: earliest starts forward through the topological order, latest
+/// starts backward. O(n + e).
+pub fn critical_path<C>(graph: &Graph, cost: C) -> Result<CriticalPath, Cycle>
+    where C: Fn(char) -> i32
+{
+    let order = graph.topological_order()?;
+    let position: HashMap<char, usize> = order.iter()
+        .enumerate()
+        .map(|(i, step)| (*step, i))
+        .collect();
+
+    let at = |step: char| position[&step];
+    let durations: Vec<i32> = order.iter().map(|step| cost(*step)).collect();
+
+    let mut earliest = vec![0; order.len()];
+    for (i, step) in order.iter().enumerate() {
+        earliest[i] = graph.prev(*step).iter()
+            .map(|prev| earliest[at(*prev)] + durations[at(*prev)])
+            .max()
+            .unwrap_or(0);
+    }
+
+    let duration = (0..order.len())
+        .map(|i| earliest[i] + durations[i])
+        .max()
+        .unwrap_or(0);
+
+    let mut latest = vec![0; order.len()];
+    for (i, step) in order.iter().enumerate().rev() {
+        latest[i] = graph.next(*step).iter()
+            .map(|next| latest[at(*next)])
+            .min()
+            .unwrap_or(duration) - durations[i];
+    }
+
+    let timings: Vec<Timing> = order.iter()
+        .enumerate()
+        .map(|(i, step)| Timing {
+            step: *step,
+            duration: durations[i],
+            earliest_start: earliest[i],
+            latest_start: latest[i],
+            slack: latest[i] - earliest[i],
+        })
+        .collect();
+
+    let critical = |step: char, start: i32| {
+        let timing = &timings[at(step)];
+        timing.slack == 0 && timing.earliest_start == start
+    };
+
+    let mut chain = Vec::new();
+    let mut current = graph.steps().into_iter()
+        .find(|step| critical(*step, 0));
+
+    while let Some(step) = current {
+        chain.push(step);
+
+        let end = timings[at(step)].earliest_start + timings[at(step)].duration;
+        current = graph.next(step).iter()
+            .cloned()
+            .find(|next| critical(*next, end));
+    }
+
+    Ok(CriticalPath { timings, duration, chain })
+}
+
+impl CriticalPath {
+    /// The fewest workers with which the schedule of puzzle 2 is done within the critical path
+    /// duration. There is no point in hiring more. Starts from the total work divided by the
+    /// duration, below that there is too much work anyway.
+    pub fn workers_needed<C>(&self, graph: &Graph, cost: C) -> usize
+        where C: Fn(char) -> i32
+    {
+        if self.duration == 0 {
+            return 1;
+        }
+
+        let total: i32 = self.timings.iter().map(|timing| timing.duration).sum();
+        let mut workers = ((total + self.duration - 1) / self.duration).max(1) as usize;
+
+        // with a worker for every step, every step starts as soon as it can
+        while workers < self.timings.len() {
+            let schedule = schedule(graph, &vec![1; workers], &cost)
+                .expect("The graph has no cycles");
+
+            if schedule.duration() <= self.duration {
+                break;
+            }
+            workers += 1;
+        }
+        workers
+    }
+}
+
+impl fmt::Display for CriticalPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "step  duration  earliest  latest  slack")?;
+
+        for timing in &self.timings {
+            writeln!(f, "{:>4}  {:>8}  {:>8}  {:>6}  {:>5}",
+                     timing.step, timing.duration, timing.earliest_start, timing.latest_start, timing.slack)?;
+        }
+
+        let chain: Vec<String> = self.chain.iter().map(|step| step.to_string()).collect();
+        write!(f, "critical path: {} ({} seconds)", chain.join(" -> "), self.duration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dependency::Dependency;
+    use crate::graph::example;
+
+    fn position(step: char) -> i32 {
+        step as i32 - 'A' as i32 + 1
+    }
+
+    fn timing(path: &CriticalPath, step: char) -> &Timing {
+        path.timings.iter().find(|timing| timing.step == step).unwrap()
+    }
+
+    #[test]
+    fn test_example() {
+        let path = critical_path(&example(), position).unwrap();
+
+        // C 0-3, F 3-9, E 9-14; A, B and D have to be done by 9 as well
+        assert_eq!(path.duration, 14);
+        assert_eq!(path.chain, vec!['C', 'F', 'E']);
+        assert_eq!(timing(&path, 'F'), &Timing { step: 'F', duration: 6, earliest_start: 3, latest_start: 3, slack: 0 });
+        assert_eq!(timing(&path, 'A'), &Timing { step: 'A', duration: 1, earliest_start: 3, latest_start: 4, slack: 1 });
+        assert_eq!(timing(&path, 'B'), &Timing { step: 'B', duration: 2, earliest_start: 4, latest_start: 7, slack: 3 });
+        assert_eq!(timing(&path, 'D'), &Timing { step: 'D', duration: 4, earliest_start: 4, latest_start: 5, slack: 1 });
+    }
+
+    #[test]
+    fn test_workers_needed() {
+        let graph = example();
+        let path = critical_path(&graph, position).unwrap();
+
+        // with two workers, D has to wait until B is done and the example takes 15 seconds
+        assert_eq!(path.workers_needed(&graph, position), 3);
+        assert_eq!(schedule(&graph, &[1, 1, 1], position).unwrap().duration(), 14);
+    }
+
+    #[test]
+    fn test_chain_splits() {
+        let path = critical_path(&example(), |_| 1).unwrap();
+
+        assert_eq!(path.duration, 4);
+        assert_eq!(path.chain, vec!['C', 'A', 'B', 'E']);
+        assert_eq!(timing(&path, 'F').slack, 1);
+    }
+
+    #[test]
+    fn test_input() {
+        let input: Vec<&str> = include_str!("../input.txt").lines().collect();
+        let graph = Graph::new(&Dependency::parse_vec(&input));
+        let cost = |step| position(step) + 60;
+
+        let path = critical_path(&graph, cost).unwrap();
+        let workers = path.workers_needed(&graph, cost);
+
+        assert_eq!(schedule(&graph, &vec![1; workers], cost).unwrap().duration(), path.duration);
+        assert!(schedule(&graph, &vec![1; workers - 1], cost).unwrap().duration() > path.duration);
+        assert!(path.timings.iter().all(|timing| timing.slack >= 0));
+    }
+}
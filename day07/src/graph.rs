@@ -0,0 +1,231 @@
+use crate::dependency::Dependency;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::fmt;
+
+/// The steps and which steps have to be finished before others can begin.
+#[derive(Debug)]
+pub struct Graph {
+    /// for every step, the steps that depend on it
+    next: BTreeMap<char, Vec<char>>,
+    /// for every step, the steps it depends on
+    prev: BTreeMap<char, Vec<char>>,
+}
+
+/// Steps that depend on each other in a circle: every step has to be finished before the next
+/// one, and the last one before the first. Starts with the first step alphabetically.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cycle {
+    pub steps: Vec<char>,
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let steps: Vec<String> = self.steps.iter()
+            .chain(self.steps.first())
+            .map(|step| step.to_string())
+            .collect();
+
+        write!(f, "the steps depend on each other: {}", steps.join(" -> "))
+    }
+}
+
+impl Graph {
+    pub fn new(dependencies: &[Dependency]) -> Graph {
+        let mut next: BTreeMap<char, Vec<char>> = BTreeMap::new();
+        let mut prev: BTreeMap<char, Vec<char>> = BTreeMap::new();
+
+        for dependency in dependencies {
+            next.entry(dependency.depends_on).or_default().push(dependency.step);
+            prev.entry(dependency.step).or_default().push(dependency.depends_on);
+
+            next.entry(dependency.step).or_default();
+            prev.entry(dependency.depends_on).or_default();
+        }
+
+        for steps in next.values_mut().chain(prev.values_mut()) {
+            steps.sort();
+            steps.dedup();
+        }
+
+        Graph { next, prev }
+    }
+
+    /// All steps, in alphabetical order.
+    pub fn steps(&self) -> Vec<char> {
+        self.next.keys().cloned().collect()
+    }
+
+    /// The steps that can only begin after `step` is finished.
+    pub fn next(&self, step: char) -> &[char] {
+        self.next.get(&step).map_or(&[], |steps| steps.as_slice())
+    }
+
+    /// The steps that have to be finished before `step` can begin.
+    pub fn prev(&self, step: char) -> &[char] {
+        self.prev.get(&step).map_or(&[], |steps| steps.as_slice())
+    }
+
+    /// For every step, the amount of steps it depends on.
+    pub fn in_degrees(&self) -> BTreeMap<char, usize> {
+        self.prev.iter()
+            .map(|(step, prev)| (*step, prev.len()))
+            .collect()
+    }
+
+    /// The order to do the steps in one by one: of the steps that can begin, always the first
+    /// alphabetically. Kahn's algorithm with a priority queue, O(e + n log n).
+    pub fn topological_order(&self) -> Result<Vec<char>, Cycle> {
+        let mut in_degrees = self.in_degrees();
+        let mut free: BinaryHeap<Reverse<char>> = in_degrees.iter()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(step, _)| Reverse(*step))
+            .collect();
+
+        let mut order = Vec::with_capacity(in_degrees.len());
+
+        while let Some(Reverse(step)) = free.pop() {
+            order.push(step);
+
+            for next in self.next(step) {
+                let degree = in_degrees.get_mut(next).expect("Every step has an in-degree");
+
+                *degree -= 1;
+                if *degree == 0 {
+                    free.push(Reverse(*next));
+                }
+            }
+        }
+
+        if order.len() < in_degrees.len() {
+            let stuck: Vec<char> = in_degrees.into_iter()
+                .filter(|(_, degree)| *degree > 0)
+                .map(|(step, _)| step)
+                .collect();
+
+            return Err(self.find_cycle(&stuck));
+        }
+        Ok(order)
+    }
+
+    /// Every stuck step still depends on another stuck step: walking back along those will
+    /// eventually visit a step twice.
+    fn find_cycle(&self, stuck: &[char]) -> Cycle {
+        let mut path = vec![stuck[0]];
+
+        loop {
+            let current = *path.last().unwrap();
+            let prev = *self.prev(current).iter()
+                .find(|step| stuck.contains(step))
+                .expect("A stuck step depends on another stuck step");
+
+            if let Some(start) = path.iter().position(|step| *step == prev) {
+                let mut steps = path.split_off(start);
+                steps.reverse();
+
+                let first = (0..steps.len()).min_by_key(|i| steps[*i]).unwrap();
+                steps.rotate_left(first);
+
+                return Cycle { steps };
+            }
+            path.push(prev);
+        }
+    }
+
+    /// The graph in the Graphviz DOT language, steps are labeled with their position in the
+    /// topological order if there is one.
+    pub fn to_dot(&self) -> String {
+        let order = self.topological_order().ok();
+        let mut dot = String::from("digraph steps {\n");
+
+        for step in self.next.keys() {
+            let label = order.as_ref()
+                .and_then(|order| order.iter().position(|s| s == step))
+                .map_or(step.to_string(), |i| format!("{}: {}", i + 1, step));
+
+            dot.push_str(&format!("    {} [label=\"{}\"];\n", step, label));
+        }
+        for (step, next) in &self.next {
+            for next in next {
+                dot.push_str(&format!("    {} -> {};\n", step, next));
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// The steps of the example in the puzzle.
+#[cfg(test)]
+pub(crate) fn example() -> Graph {
+    let dependencies: Vec<Dependency> = [
+        "Step C must be finished before step A can begin.",
+        "Step C must be finished before step F can begin.",
+        "Step A must be finished before step B can begin.",
+        "Step A must be finished before step D can begin.",
+        "Step B must be finished before step E can begin.",
+        "Step D must be finished before step E can begin.",
+        "Step F must be finished before step E can begin.",
+    ].iter()
+        .map(|line| Dependency::parse(line))
+        .collect();
+
+    Graph::new(&dependencies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(lines: &[&str]) -> Graph {
+        let dependencies: Vec<Dependency> = lines.iter()
+            .map(|line| Dependency::parse(line))
+            .collect();
+
+        Graph::new(&dependencies)
+    }
+
+    #[test]
+    fn test_new() {
+        let graph = example();
+
+        assert_eq!(graph.steps(), vec!['A', 'B', 'C', 'D', 'E', 'F']);
+        assert_eq!(graph.next('A'), &['B', 'D']);
+        assert_eq!(graph.prev('E'), &['B', 'D', 'F']);
+        assert_eq!(graph.next('Z'), &[]);
+        assert_eq!(graph.in_degrees().get(&'C'), Some(&0));
+        assert_eq!(graph.in_degrees().get(&'E'), Some(&3));
+    }
+
+    #[test]
+    fn test_topological_order() {
+        assert_eq!(example().topological_order(), Ok("CABDFE".chars().collect()));
+    }
+
+    #[test]
+    fn test_cycle() {
+        let graph = graph(&[
+            "Step A must be finished before step B can begin.",
+            "Step B must be finished before step C can begin.",
+            "Step C must be finished before step D can begin.",
+            "Step D must be finished before step B can begin.",
+            "Step D must be finished before step E can begin.",
+        ]);
+
+        let cycle = graph.topological_order().unwrap_err();
+
+        assert_eq!(cycle, Cycle { steps: vec!['B', 'C', 'D'] });
+        assert_eq!(cycle.to_string(), "the steps depend on each other: B -> C -> D -> B");
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let dot = example().to_dot();
+
+        assert!(dot.starts_with("digraph steps {\n    A [label=\"2: A\"];\n"));
+        assert!(dot.contains("    C [label=\"1: C\"];\n"));
+        assert!(dot.contains("    A -> B;\n    A -> D;\n"));
+        assert!(dot.ends_with("    F -> E;\n}\n"));
+    }
+}
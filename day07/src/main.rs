@@ -1,132 +1,162 @@
+mod critical_path;
 mod dependency;
+mod graph;
+mod schedule;
 
+use crate::critical_path::critical_path;
 use crate::dependency::*;
-use std::collections::HashMap;
+use crate::graph::{Cycle, Graph};
+use crate::schedule::{schedule, Schedule};
+use std::path::Path;
+use structopt::StructOpt;
+use util::params::Params;
+use util::runner::{Day, RunnerOpt};
+
+#[derive(StructOpt, Debug)]
+struct Opt {
+    /// Print the steps as a Graphviz DOT graph instead of solving the puzzles
+    #[structopt(long = "dot")]
+    dot: bool,
+
+    /// Print the schedule of puzzle 2 as a Gantt chart instead of solving the puzzles
+    #[structopt(long = "gantt")]
+    gantt: bool,
+
+    /// Print the schedule of puzzle 2 as CSV instead of solving the puzzles
+    #[structopt(long = "csv")]
+    csv: bool,
+
+    /// Print the earliest and latest start of every step, the critical path and how many
+    /// workers it takes to be done that fast, instead of solving the puzzles
+    #[structopt(long = "critical-path")]
+    critical_path: bool,
+
+    #[structopt(flatten)]
+    runner: RunnerOpt,
+}
 
-fn get_input() -> Vec<&'static str> {
-    include_str!("../input.txt")
-        .split("\n")
+fn parse_input(input: &str) -> Vec<&str> {
+    input.split("\n")
         .filter(|s| s.len() > 0)
         .collect()
 }
 
 fn main() {
-    let input = get_input();
+    let opt = Opt::from_args();
 
-    println!("Puzzle 1: {}", puzzle_1(&input));
-    println!("Puzzle 2: {}", puzzle_2(&input, 5, |c| char_to_num(c) + 60));
-}
+    if opt.dot || opt.gantt || opt.csv || opt.critical_path {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let file = opt.runner.input.clone()
+            .unwrap_or_else(|| dir.join("input.txt"));
 
-fn char_to_num(c: char) -> i32 {
-    "_ABCDEFGHIJKLMNOPQRSTUVWXYZ".find(c).unwrap() as i32
-}
+        let input = std::fs::read_to_string(&file)
+            .expect("Could not read input file");
+        let graph = Graph::new(&Dependency::parse_vec(&parse_input(&input)));
 
-fn get_unique_steps_sorted(dependencies: &Vec<Dependency>) -> Vec<char> {
-    let mut steps = Vec::with_capacity(dependencies.len());
+        let params = opt.runner.param_file(dir)
+            .unwrap_or_else(|e| panic!("{}", e))
+            .for_input(&input);
 
-    for dependency in dependencies {
-        steps.push(dependency.step);
-        steps.push(dependency.depends_on);
-    }
-
-    steps.sort();
-    steps.dedup();
-
-    steps
-}
+        let result = Workers::from(&params)
+            .and_then(|workers| report(&opt, &graph, &workers).map_err(|cycle| cycle.to_string()));
 
-fn get_free_steps(steps: &Vec<char>, dependencies: &Vec<Dependency>) -> Vec<char> {
-    steps.iter()
-        .filter(|step| {
-            dependencies.iter()
-                .filter(|dependency| dependency.step == **step)
-                .count() == 0
-        })
-        .map(|&c| c)
-        .collect()
-}
+        if let Err(e) = result {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
 
-fn remove_from(list: &mut Vec<char>, to_remove: &Vec<char>) {
-    list.retain(|step| !to_remove.contains(step));
+    Day::try_with_params(env!("CARGO_MANIFEST_DIR"), |input, params| Ok::<_, String>((parse_input(input), Workers::from(params)?)))
+        .try_puzzle(|(input, _)| puzzle_1(input))
+        .try_puzzle(|(input, workers)| puzzle_2(input, workers))
+        .run_with(opt.runner);
 }
 
-fn remove_from_deps(list: &mut Vec<Dependency>, to_remove: &Vec<char>) {
-    list.retain(|dependency| !to_remove.contains(&dependency.depends_on));
+/// The workers of puzzle 2 and how long steps take them.
+#[derive(Clone, Debug, PartialEq)]
+struct Workers {
+    /// how much work each worker does per second
+    speeds: Vec<i32>,
+    base_duration: i32,
 }
 
-fn do_the_work<Work: Fn(char) -> i32>(mut dependencies: Vec<Dependency>, workers: i32, calculate_work: Work) -> (String, i32) {
-    let mut steps_todo = get_unique_steps_sorted(&dependencies);
-    let mut steps_working_on = Vec::<char>::new();
-    let mut steps_done = Vec::<char>::new();
-
-    let mut elapsed_seconds = 0;
+impl Workers {
+    /// `speeds` if given, otherwise `workers` workers with speed 1.
+    fn from(params: &Params) -> Result<Workers, String> {
+        let speeds = if params.contains("speeds") {
+            params.get::<Vec<i32>>("speeds")?
+        } else {
+            vec![1; params.get::<usize>("workers")?]
+        };
 
-    let mut steps_work_left: HashMap<_,_> = steps_todo.iter()
-        .map(|&step| (step, calculate_work(step)))
-        .collect();
+        let workers = Workers { speeds, base_duration: params.get::<i32>("base_duration")? };
 
-    loop {
-        // if workers are idle, take on some new work
-        let workers_idle = (workers as usize) - steps_working_on.len();
-        if workers_idle > 0 {
-            let mut steps_free = get_free_steps(&steps_todo, &dependencies);
-
-            steps_free.truncate(workers_idle);
-            for step in &steps_free {
-                steps_working_on.push(*step);
-            }
+        workers.validate()?;
+        Ok(workers)
+    }
 
-            remove_from(&mut steps_todo, &steps_working_on);
+    /// Whether the steps can be scheduled: there has to be a worker, and every worker has to get
+    /// some work done.
+    fn validate(&self) -> Result<(), String> {
+        if self.speeds.is_empty() {
+            return Err("there have to be workers".to_string());
         }
-
-        // println!("* loop: {}", elapsed_seconds);
-        // println!("todo: {:?}", steps_todo);
-        // println!("work: {:?}", steps_working_on);
-        // println!("done: {:?}", steps_done);
-        // println!("time: {:?}", steps_work_left);
-
-        // do the work
-        for step in &steps_working_on {
-            let work_left = steps_work_left.get_mut(&step)
-                .expect("Found no work time for this step");
-
-            *work_left -= 1;
-
-            if *work_left == 0 {
-                steps_done.push(*step);
-            }
+        if let Some(speed) = self.speeds.iter().find(|speed| **speed <= 0) {
+            return Err(format!("a speed of {} gets nothing done, speeds have to be positive", speed));
         }
+        Ok(())
+    }
+}
 
-        // bookkeeping
-        remove_from(&mut steps_working_on, &steps_done);
-        remove_from_deps(&mut dependencies, &steps_done);
+/// Print what was asked for on the command line instead of the answers.
+fn report(opt: &Opt, graph: &Graph, workers: &Workers) -> Result<(), Cycle> {
+    if opt.dot {
+        print!("{}", graph.to_dot());
+    } else if opt.critical_path {
+        let cost = |c| char_to_num(c) + workers.base_duration;
+        let path = critical_path(graph, cost)?;
+
+        println!("{}", path);
+        println!("workers needed: {}", path.workers_needed(graph, cost));
+    } else if opt.csv {
+        print!("{}", build_schedule(graph, workers)?.to_csv());
+    } else {
+        let schedule = build_schedule(graph, workers)?;
+
+        print!("{}", schedule.to_gantt(100));
+        println!("order: {}", schedule.order());
+    }
+    Ok(())
+}
 
-        elapsed_seconds += 1;
+fn build_schedule(graph: &Graph, workers: &Workers) -> Result<Schedule, Cycle> {
+    schedule(graph, &workers.speeds, |c| char_to_num(c) + workers.base_duration)
+}
 
-        if steps_todo.is_empty() && steps_working_on.is_empty() {
-            break;
-        }
-    }
-    (steps_done.into_iter().collect(), elapsed_seconds)
+fn char_to_num(c: char) -> i32 {
+    "_ABCDEFGHIJKLMNOPQRSTUVWXYZ".find(c).unwrap() as i32
 }
 
-fn puzzle_1(input: &Vec<&str>) -> String {
-    let dependencies = Dependency::parse_vec(&input);
+fn puzzle_1(input: &Vec<&str>) -> Result<String, Cycle> {
+    let dependencies = Dependency::parse_vec(input);
 
-    do_the_work(dependencies, 1, |_| { 1 })
-        .0
+    Graph::new(&dependencies)
+        .topological_order()
+        .map(|order| order.into_iter().collect())
 }
 
-fn puzzle_2<Work: Fn(char) -> i32>(input: &Vec<&str>, workers: i32, calculate_work: Work) -> i32 {
-    let dependencies = Dependency::parse_vec(&input);
+fn puzzle_2(input: &Vec<&str>, workers: &Workers) -> Result<i32, Cycle> {
+    let dependencies = Dependency::parse_vec(input);
 
-    do_the_work(dependencies, workers, calculate_work)
-        .1
+    build_schedule(&Graph::new(&dependencies), workers)
+        .map(|schedule| schedule.duration())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use util::params::ParamFile;
 
     #[test]
     fn test_char_to_num() {
@@ -147,7 +177,7 @@ mod tests {
         example.push("Step D must be finished before step E can begin.");
         example.push("Step F must be finished before step E can begin.");
 
-        assert_eq!(puzzle_1(&example), "CABDFE".to_string());
+        assert_eq!(puzzle_1(&example), Ok("CABDFE".to_string()));
     }
 
     #[test]
@@ -161,6 +191,39 @@ mod tests {
         example.push("Step D must be finished before step E can begin.");
         example.push("Step F must be finished before step E can begin.");
 
-        assert_eq!(puzzle_2(&example, 2, |c| char_to_num(c)), 15);
+        assert_eq!(puzzle_2(&example, &Workers { speeds: vec![1, 1], base_duration: 0 }), Ok(15));
+    }
+
+    #[test]
+    fn test_workers() {
+        let params = |toml: &str| ParamFile::parse(toml).unwrap().for_input("");
+
+        assert_eq!(Workers::from(&params("workers = 2\nbase_duration = 60")), Ok(Workers { speeds: vec![1, 1], base_duration: 60 }));
+        assert_eq!(Workers::from(&params("workers = 2\nbase_duration = 0\nspeeds = [1, 3]")), Ok(Workers { speeds: vec![1, 3], base_duration: 0 }));
+
+        // a malformed `speeds` is an error, not a reason to use `workers`
+        assert_eq!(
+            Workers::from(&params("workers = 2\nbase_duration = 0\nspeeds = [1.5, 0.5]")),
+            Err("Invalid value for parameter `speeds`: [1.5, 0.5]".to_string())
+        );
+        assert!(Workers::from(&params("workers = 2\nbase_duration = 0\nspeeds = 3")).is_err());
+
+        assert_eq!(Workers::from(&params("workers = 2\nbase_duration = 0\nspeeds = []")), Err("there have to be workers".to_string()));
+        assert_eq!(
+            Workers::from(&params("workers = 2\nbase_duration = 0\nspeeds = [2, 0]")),
+            Err("a speed of 0 gets nothing done, speeds have to be positive".to_string())
+        );
+        assert!(Workers::from(&params("workers = 0\nbase_duration = 0")).is_err());
+    }
+
+    #[test]
+    fn test_puzzle_cycle() {
+        let example = vec![
+            "Step A must be finished before step B can begin.",
+            "Step B must be finished before step A can begin.",
+        ];
+
+        assert!(puzzle_1(&example).is_err());
+        assert_eq!(puzzle_2(&example, &Workers { speeds: vec![1, 1], base_duration: 0 }), Err(Cycle { steps: vec!['A', 'B'] }));
     }
 }
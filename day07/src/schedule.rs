@@ -0,0 +1,216 @@
+use crate::graph::{Cycle, Graph};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// One step done by one worker, from `start` up to (not including) `end`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Task {
+    pub worker: usize,
+    pub step: char,
+    pub start: i32,
+    pub end: i32,
+}
+
+/// Who does which step when.
+#[derive(Debug)]
+pub struct Schedule {
+    /// in the order they were started
+    pub tasks: Vec<Task>,
+    workers: usize,
+}
+
+/// Let workers do all steps as soon as possible. Whenever workers are idle, the steps that can
+/// begin are handed out alphabetically, the fastest worker first. A step of `cost` takes a worker
+/// with speed `s` cost / s seconds, rounded up.
+///
+/// Time jumps from one finished step to the next instead of ticking every second, so this takes
+/// O(n log n) however long the steps take. Panics if there are no workers or a speed isn't
+/// positive.
+pub fn schedule<C>(graph: &Graph, speeds: &[i32], cost: C) -> Result<Schedule, Cycle>
+    where C: Fn(char) -> i32
+{
+    assert!(!speeds.is_empty(), "There are no workers");
+    assert!(speeds.iter().all(|speed| *speed > 0), "Speeds have to be positive");
+
+    // the workers would wait forever on steps that depend on each other
+    graph.topological_order()?;
+
+    let mut in_degrees = graph.in_degrees();
+    let mut free: BinaryHeap<Reverse<char>> = in_degrees.iter()
+        .filter(|(_, degree)| **degree == 0)
+        .map(|(step, _)| Reverse(*step))
+        .collect();
+    let mut idle: BinaryHeap<(i32, Reverse<usize>)> = speeds.iter()
+        .enumerate()
+        .map(|(worker, speed)| (*speed, Reverse(worker)))
+        .collect();
+    let mut running: BinaryHeap<Reverse<(i32, char, usize)>> = BinaryHeap::new();
+
+    let mut tasks = Vec::with_capacity(in_degrees.len());
+    let mut time = 0;
+
+    loop {
+        while !idle.is_empty() && !free.is_empty() {
+            let Reverse(step) = free.pop().unwrap();
+            let (speed, Reverse(worker)) = idle.pop().unwrap();
+
+            let end = time + (cost(step) + speed - 1) / speed;
+
+            running.push(Reverse((end, step, worker)));
+            tasks.push(Task { worker, step, start: time, end });
+        }
+
+        time = match running.peek() {
+            Some(Reverse((end, _, _)))  => *end,
+            None                        => break,
+        };
+
+        // everything that finishes now, before handing out new steps
+        while let Some(Reverse((end, step, worker))) = running.peek().cloned() {
+            if end > time {
+                break;
+            }
+            running.pop();
+            idle.push((speeds[worker], Reverse(worker)));
+
+            for next in graph.next(step) {
+                let degree = in_degrees.get_mut(next).expect("Every step has an in-degree");
+
+                *degree -= 1;
+                if *degree == 0 {
+                    free.push(Reverse(*next));
+                }
+            }
+        }
+    }
+
+    Ok(Schedule { tasks, workers: speeds.len() })
+}
+
+impl Schedule {
+    /// The time at which all steps are done.
+    pub fn duration(&self) -> i32 {
+        self.tasks.iter()
+            .map(|task| task.end)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The steps in the order they were finished.
+    pub fn order(&self) -> String {
+        let mut tasks: Vec<&Task> = self.tasks.iter().collect();
+        tasks.sort_by_key(|task| (task.end, task.step));
+
+        tasks.into_iter()
+            .map(|task| task.step)
+            .collect()
+    }
+
+    /// A Gantt chart with a row per worker, at most `width` columns wide. Every column shows
+    /// what the worker is doing at the start of its period, `.` if it is idle.
+    pub fn to_gantt(&self, width: usize) -> String {
+        let width = width.max(1);
+        let duration = self.duration() as usize;
+        let scale = duration.div_ceil(width).max(1);
+        let columns = duration.div_ceil(scale);
+
+        let mut rows = vec![vec!['.'; columns]; self.workers];
+
+        for task in &self.tasks {
+            for (column, cell) in rows[task.worker].iter_mut().enumerate() {
+                let time = (column * scale) as i32;
+
+                if task.start <= time && time < task.end {
+                    *cell = task.step;
+                }
+            }
+        }
+
+        let mut chart = format!("{} second(s) per column, {} seconds in total\n", scale, duration);
+
+        for (worker, row) in rows.into_iter().enumerate() {
+            chart.push_str(&format!("worker {:>2} |{}|\n", worker + 1, row.into_iter().collect::<String>()));
+        }
+        chart
+    }
+
+    /// One line per task, workers are numbered from 1.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("worker,step,start,end\n");
+
+        for task in &self.tasks {
+            csv.push_str(&format!("{},{},{},{}\n", task.worker + 1, task.step, task.start, task.end));
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::example;
+
+    fn position(step: char) -> i32 {
+        step as i32 - 'A' as i32 + 1
+    }
+
+    #[test]
+    fn test_example() {
+        let schedule = schedule(&example(), &[1, 1], position).unwrap();
+
+        assert_eq!(schedule.duration(), 15);
+        assert_eq!(schedule.order(), "CABFDE");
+        assert_eq!(schedule.tasks[2], Task { worker: 1, step: 'F', start: 3, end: 9 });
+    }
+
+    #[test]
+    fn test_one_worker() {
+        let schedule = schedule(&example(), &[1], |_| 1).unwrap();
+
+        assert_eq!(schedule.order(), "CABDFE");
+        assert_eq!(schedule.duration(), 6);
+    }
+
+    #[test]
+    fn test_speeds() {
+        // the fast worker gets the first step, and 5 work at speed 2 takes 3 seconds
+        let schedule = schedule(&example(), &[1, 2], |_| 5).unwrap();
+
+        assert_eq!(schedule.tasks[0], Task { worker: 1, step: 'C', start: 0, end: 3 });
+        assert_eq!(schedule.tasks[1], Task { worker: 1, step: 'A', start: 3, end: 6 });
+        assert_eq!(schedule.tasks[2], Task { worker: 0, step: 'F', start: 3, end: 8 });
+    }
+
+    #[test]
+    fn test_long_steps() {
+        let schedule = schedule(&example(), &[1, 1], |step| 1_000_000 * position(step)).unwrap();
+
+        assert_eq!(schedule.duration(), 15_000_000);
+    }
+
+    #[test]
+    fn test_gantt() {
+        let schedule = schedule(&example(), &[1, 1], position).unwrap();
+
+        assert_eq!(
+            schedule.to_gantt(80),
+            "1 second(s) per column, 15 seconds in total\n\
+             worker  1 |CCCABBDDDDEEEEE|\n\
+             worker  2 |...FFFFFF......|\n"
+        );
+        assert_eq!(
+            schedule.to_gantt(5),
+            "3 second(s) per column, 15 seconds in total\n\
+             worker  1 |CADDE|\n\
+             worker  2 |.FF..|\n"
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        let csv = schedule(&example(), &[1, 1], position).unwrap().to_csv();
+
+        assert!(csv.starts_with("worker,step,start,end\n1,C,0,3\n1,A,3,4\n2,F,3,9\n"));
+        assert!(csv.ends_with("1,E,10,15\n"));
+    }
+}
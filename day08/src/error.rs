@@ -0,0 +1,65 @@
+use std::fmt;
+
+/// Where the input stops being a tree. `position` is the index of the offending number (starting
+/// at 0) and `offset` its byte offset in the input, both point at the end of the input if the
+/// input ends too early.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    pub position: usize,
+    pub offset: usize,
+    pub kind: ErrorKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ErrorKind {
+    /// The number isn't a valid value for the field, contains the number itself.
+    InvalidNumber(Field, String),
+    /// The input ends while the field is still expected.
+    Truncated(Field),
+    /// The tree is complete but the input isn't, contains the amount of numbers left.
+    TrailingInput(usize),
+}
+
+/// The numbers a node consists of.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Field {
+    ChildCount,
+    MetadataCount,
+    Metadata,
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Field::ChildCount       => write!(f, "the amount of children"),
+            Field::MetadataCount    => write!(f, "the amount of metadata entries"),
+            Field::Metadata         => write!(f, "a metadata entry"),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "number {} (byte {}): ", self.position + 1, self.offset)?;
+
+        match self.kind {
+            ErrorKind::InvalidNumber(field, ref number) => write!(f, "`{}` is not valid as {}", number, field),
+            ErrorKind::Truncated(field)                 => write!(f, "input ends while expecting {}", field),
+            ErrorKind::TrailingInput(left)              => write!(f, "tree is complete but {} more number(s) follow", left),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let error = ParseError { position: 4, offset: 8, kind: ErrorKind::Truncated(Field::Metadata) };
+        assert_eq!(error.to_string(), "number 5 (byte 8): input ends while expecting a metadata entry");
+
+        let error = ParseError { position: 0, offset: 0, kind: ErrorKind::InvalidNumber(Field::ChildCount, "-1".to_string()) };
+        assert_eq!(error.to_string(), "number 1 (byte 0): `-1` is not valid as the amount of children");
+    }
+}
@@ -1,25 +1,81 @@
+mod error;
 mod node;
 
-use crate::node::Node;
+use crate::error::ParseError;
+use crate::node::Tree;
+use std::path::Path;
+use structopt::StructOpt;
+use util::runner::{Day, RunnerOpt};
+
+#[derive(StructOpt, Debug)]
+struct Opt {
+    /// Print the tree with the value and metadata sum of every node instead of solving the puzzles
+    #[structopt(long = "tree")]
+    tree: bool,
+
+    /// Print the node at a path like `root/1/0` (children numbered from 0) instead of solving
+    /// the puzzles
+    #[structopt(long = "path")]
+    path: Option<String>,
+
+    #[structopt(flatten)]
+    runner: RunnerOpt,
+}
 
 fn main() {
-    let input = include_str!("../input.txt")
-        .trim();
+    let opt = Opt::from_args();
+
+    if opt.tree || opt.path.is_some() {
+        let file = opt.runner.input.clone()
+            .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("input.txt"));
+
+        let input = std::fs::read_to_string(&file)
+            .expect("Could not read input file");
+
+        let report = Tree::parse(&input)
+            .map_err(|error| error.to_string())
+            .and_then(|tree| match opt.path {
+                Some(ref path)  => describe(&tree, path),
+                None            => Ok(tree.to_pretty()),
+            });
+
+        match report {
+            Ok(report)  => print!("{}", report),
+            Err(error)  => {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    Day::new(env!("CARGO_MANIFEST_DIR"), |input| input)
+        .try_puzzle(|input| puzzle_1(input))
+        .try_puzzle(|input| puzzle_2(input))
+        .run_with(opt.runner);
+}
+
+/// The metadata and value of the node at `path`, and its subtree as input to look at it on its own.
+fn describe(tree: &Tree, path: &str) -> Result<String, String> {
+    let id = tree.find(path)?;
+    let node = tree.node(id);
 
-    println!("Puzzle 1: {}", puzzle_1(&input));
-    println!("Puzzle 2: {}", puzzle_2(&input));
+    Ok(format!(
+        "children: {}\nmetadata: {:?}\nmetadata sum: {}\nvalue: {}\nsubtree: {}\n",
+        node.children.len(), node.metadata, tree.metadata_sums()[id], tree.values()[id], tree.encode(id)
+    ))
 }
 
-fn puzzle_1(input: &str) -> i32 {
-    let node = Node::parse(input);
+fn puzzle_1(input: &str) -> Result<i32, ParseError> {
+    let tree = Tree::parse(input)?;
 
-    node.metadata_sum()
+    Ok(tree.metadata_sum())
 }
 
-fn puzzle_2(input: &str) -> i32 {
-    let node = Node::parse(input);
+fn puzzle_2(input: &str) -> Result<i32, ParseError> {
+    let tree = Tree::parse(input)?;
 
-    node.value()
+    Ok(tree.value())
 }
 
 #[cfg(test)]
@@ -30,13 +86,24 @@ mod tests {
     fn test_puzzle_1() {
         let example = "2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2";
 
-        assert_eq!(puzzle_1(&example), 138);
+        assert_eq!(puzzle_1(example), Ok(138));
     }
 
     #[test]
     fn test_puzzle_2() {
         let example = "2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2";
 
-        assert_eq!(puzzle_2(&example), 66);
+        assert_eq!(puzzle_2(example), Ok(66));
+    }
+
+    #[test]
+    fn test_describe() {
+        let tree = Tree::parse("2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2").unwrap();
+
+        assert_eq!(
+            describe(&tree, "root/1"),
+            Ok("children: 1\nmetadata: [2]\nmetadata sum: 101\nvalue: 0\nsubtree: 1 1 0 1 99 2\n".to_string())
+        );
+        assert!(describe(&tree, "root/2").is_err());
     }
 }
@@ -1,162 +1,381 @@
-use nom::*;
-use nom::types::CompleteStr;
+use crate::error::{ErrorKind, Field, ParseError};
+use std::str::FromStr;
 
-#[derive(Debug)]
+/// A node of a `Tree`, its children are indices into the tree.
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Node {
-    children: Vec<Node>,
-    metadata: Vec<i32>,
+    pub children: Vec<usize>,
+    pub metadata: Vec<i32>,
 }
 
-impl Node {
-    pub fn parse(input: &str) -> Node {
-        parse_node(CompleteStr(input))
-            .expect("Could not parse Node")
-            .1
+/// All nodes of the license in one arena. The root is node 0 and nodes are stored in the order
+/// they appear in the input, so children always come after their parent.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tree {
+    nodes: Vec<Node>,
+}
+
+/// A node of which not all children have been read yet.
+struct Open {
+    node: usize,
+    children_left: usize,
+    metadata: usize,
+}
+
+impl Tree {
+    /// Parse the numbers of a license. Open nodes are kept on a stack instead of recursing, so
+    /// the depth of the tree doesn't matter. The input has to be exactly one tree.
+    pub fn parse(input: &str) -> Result<Tree, ParseError> {
+        let mut numbers = Numbers::new(input);
+        let mut nodes = Vec::new();
+        let mut stack = vec![Tree::open(&mut nodes, &mut numbers)?];
+
+        while let Some(open) = stack.last_mut() {
+            if open.children_left > 0 {
+                open.children_left -= 1;
+
+                let parent = open.node;
+                let child = Tree::open(&mut nodes, &mut numbers)?;
+
+                nodes[parent].children.push(child.node);
+                stack.push(child);
+            } else {
+                let open = stack.pop().unwrap();
+
+                for _ in 0..open.metadata {
+                    let entry = numbers.next(Field::Metadata)?;
+                    nodes[open.node].metadata.push(entry);
+                }
+            }
+        }
+
+        let left = numbers.remaining();
+        if left > 0 {
+            return Err(numbers.error(ErrorKind::TrailingInput(left)));
+        }
+        Ok(Tree { nodes })
+    }
+
+    /// Read the header of the next node and add it.
+    fn open(nodes: &mut Vec<Node>, numbers: &mut Numbers) -> Result<Open, ParseError> {
+        let children_left = numbers.next(Field::ChildCount)?;
+        let metadata = numbers.next(Field::MetadataCount)?;
+
+        nodes.push(Node::default());
+        Ok(Open { node: nodes.len() - 1, children_left, metadata })
+    }
+
+    pub fn node(&self, id: usize) -> &Node {
+        &self.nodes[id]
+    }
+
+    /// The node at `path`: `root`, followed by the index of a child (starting at 0) for every
+    /// level down, e.g. `root/1/0`.
+    pub fn find(&self, path: &str) -> Result<usize, String> {
+        let mut parts = path.trim_matches('/').split('/');
+
+        if parts.next() != Some("root") {
+            return Err(format!("`{}` doesn't start at `root`", path));
+        }
+
+        let mut id = 0;
+        for part in parts {
+            let index = part.parse::<usize>()
+                .map_err(|_| format!("`{}` in `{}` is not a child index", part, path))?;
+
+            id = *self.nodes[id].children.get(index)
+                .ok_or_else(|| format!("`{}` in `{}` is not a child, the node has {}", part, path, self.nodes[id].children.len()))?;
+        }
+        Ok(id)
+    }
+
+    /// The numbers of the subtree starting at node `id`, as they would appear in the input.
+    pub fn encode(&self, id: usize) -> String {
+        let mut numbers: Vec<String> = Vec::new();
+        // the nodes that still have children to encode, with the index of the next one
+        let mut stack = vec![(id, 0)];
+
+        numbers.push(self.nodes[id].children.len().to_string());
+        numbers.push(self.nodes[id].metadata.len().to_string());
+
+        while let Some((id, next)) = stack.pop() {
+            let node = &self.nodes[id];
+
+            match node.children.get(next) {
+                Some(child) => {
+                    stack.push((id, next + 1));
+                    stack.push((*child, 0));
+
+                    numbers.push(self.nodes[*child].children.len().to_string());
+                    numbers.push(self.nodes[*child].metadata.len().to_string());
+                }
+                None        => numbers.extend(node.metadata.iter().map(|entry| entry.to_string())),
+            }
+        }
+        numbers.join(" ")
     }
 
+    /// The sum of all metadata in the tree.
     pub fn metadata_sum(&self) -> i32 {
-        let mut sum = 0;
-        
-        sum += self.metadata.iter()
-            .sum::<i32>();
-        
-        sum += self.children.iter()
-            .map(|node| node.metadata_sum())
-            .sum::<i32>();
+        self.nodes.iter()
+            .flat_map(|node| node.metadata.iter())
+            .sum()
+    }
+
+    /// The sum of the metadata of every node and its descendants, computed from the last node to
+    /// the first like `values`.
+    pub fn metadata_sums(&self) -> Vec<i32> {
+        let mut sums = vec![0; self.nodes.len()];
 
-        sum
+        for (id, node) in self.nodes.iter().enumerate().rev() {
+            sums[id] = node.metadata.iter().sum::<i32>()
+                + node.children.iter().map(|child| sums[*child]).sum::<i32>();
+        }
+        sums
     }
 
+    /// The value of the root node.
     pub fn value(&self) -> i32 {
-        if self.children.is_empty() {
-            return self.metadata_sum();
+        self.values()[0]
+    }
+
+    /// The value of every node: the sum of its metadata if it has no children, otherwise the sum
+    /// of the values of the children its metadata refers to (starting at 1). Computed from the
+    /// last node to the first, so children are done before their parent.
+    pub fn values(&self) -> Vec<i32> {
+        let mut values = vec![0; self.nodes.len()];
+
+        for (id, node) in self.nodes.iter().enumerate().rev() {
+            values[id] = if node.children.is_empty() {
+                node.metadata.iter().sum()
+            } else {
+                node.metadata.iter()
+                    .filter_map(|entry| (*entry as usize).checked_sub(1))
+                    .filter_map(|index| node.children.get(index))
+                    .map(|child| values[*child])
+                    .sum()
+            };
         }
+        values
+    }
+
+    /// The tree with one node per line, children indented below their parent and labeled with
+    /// their index.
+    pub fn to_pretty(&self) -> String {
+        let values = self.values();
+        let sums = self.metadata_sums();
+
+        let mut pretty = String::new();
+        let mut stack = vec![(0, 0, "root".to_string())];
 
-        let mut value = 0;
+        while let Some((id, depth, label)) = stack.pop() {
+            let node = &self.nodes[id];
 
-        for entry in &self.metadata {
-            let index = (*entry - 1) as usize;
+            pretty.push_str(&format!(
+                "{}{}: value {}, metadata sum {}, metadata {:?}\n",
+                "  ".repeat(depth), label, values[id], sums[id], node.metadata
+            ));
 
-            if let Some(child) = self.children.get(index) {
-                value += child.value()
+            for (index, child) in node.children.iter().enumerate().rev() {
+                stack.push((*child, depth + 1, index.to_string()));
             }
         }
-        value
+        pretty
     }
 }
 
-named!(parse_i32<CompleteStr, i32>,
-    do_parse!(
-        opt!(tag!(" "))                     >>
-        int: take_while!(char::is_numeric)  >>
+/// The numbers of the input, keeping track of where they are.
+struct Numbers<'a> {
+    input: &'a str,
+    words: std::str::SplitWhitespace<'a>,
+    position: usize,
+}
 
-        (int.parse::<i32>().expect("Could not parse i32"))
-    )
-);
+impl<'a> Numbers<'a> {
+    fn new(input: &'a str) -> Numbers<'a> {
+        Numbers { input, words: input.split_whitespace(), position: 0 }
+    }
 
-named!(parse_usize<CompleteStr, usize>,
-    do_parse!(
-        opt!(tag!(" "))                     >>
-        int: take_while!(char::is_numeric)  >>
+    fn next<T: FromStr>(&mut self, field: Field) -> Result<T, ParseError> {
+        let word = match self.words.next() {
+            Some(word)  => word,
+            None        => return Err(self.error(ErrorKind::Truncated(field))),
+        };
 
-        (int.parse::<usize>().expect("Could not parse i32"))
-    )
-);
+        let offset = word.as_ptr() as usize - self.input.as_ptr() as usize;
+        let number = word.parse::<T>()
+            .map_err(|_| ParseError { position: self.position, offset, kind: ErrorKind::InvalidNumber(field, word.to_string()) })?;
 
-named!(parse_node<CompleteStr, Node>,
-    do_parse!(
-        amt_children: parse_usize                   >>
-        amt_metadata: parse_usize                   >>
+        self.position += 1;
+        Ok(number)
+    }
 
-        children: count!(parse_node, amt_children)  >>
-        metadata: count!(parse_i32, amt_metadata)   >>
+    /// The amount of numbers that haven't been read yet.
+    fn remaining(&self) -> usize {
+        self.words.clone().count()
+    }
 
-        (Node { children, metadata })
-    )
-);
+    /// An error at the next number, or at the end of the input if there is none.
+    fn error(&self, kind: ErrorKind) -> ParseError {
+        let offset = self.words.clone()
+            .next()
+            .map_or(self.input.len(), |word| word.as_ptr() as usize - self.input.as_ptr() as usize);
+
+        ParseError { position: self.position, offset, kind }
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2";
+
+    fn node(children: Vec<usize>, metadata: Vec<i32>) -> Node {
+        Node { children, metadata }
+    }
+
     #[test]
     fn test_parse_metadata_only() {
-        let node_a = Node::parse("0 1 1");
+        let tree_a = Tree::parse("0 1 1").unwrap();
 
-        assert_eq!(node_a.children.len(), 0);
-        assert_eq!(node_a.metadata.len(), 1);
-        assert_eq!(node_a.metadata, vec![1]);
+        assert_eq!(tree_a.metadata_sums(), vec![1]);
+        assert_eq!(tree_a.node(0), &node(vec![], vec![1]));
 
-        let node_b = Node::parse("0 3 50 25 100");
+        let tree_b = Tree::parse("0 3 50 25 100").unwrap();
 
-        assert_eq!(node_b.children.len(), 0);
-        assert_eq!(node_b.metadata.len(), 3);
-        assert_eq!(node_b.metadata, vec![50, 25, 100]);
+        assert_eq!(tree_b.node(0), &node(vec![], vec![50, 25, 100]));
     }
 
     #[test]
     fn test_parse_nested() {
-        let node_a = Node::parse("1 1 0 1 50 100");
+        let tree = Tree::parse("1 1 0 1 50 100").unwrap();
 
-        assert_eq!(node_a.children.len(), 1);
-        assert_eq!(node_a.metadata, vec![100]);
+        assert_eq!(tree.node(0), &node(vec![1], vec![100]));
+        assert_eq!(tree.node(1), &node(vec![], vec![50]));
+    }
 
-        let node_b = node_a.children.get(0).unwrap();
+    #[test]
+    fn test_parse_example() {
+        let tree = Tree::parse(EXAMPLE).unwrap();
 
-        assert_eq!(node_b.children.len(), 0);
-        assert_eq!(node_b.metadata, vec![50]);
+        assert_eq!(tree.metadata_sums().len(), 4);
+        assert_eq!(tree.node(0), &node(vec![1, 2], vec![1, 1, 2]));
+        assert_eq!(tree.node(1), &node(vec![], vec![10, 11, 12]));
+        assert_eq!(tree.node(2), &node(vec![3], vec![2]));
+        assert_eq!(tree.node(3), &node(vec![], vec![99]));
     }
 
     #[test]
-    fn test_parse_example() {
-        let node_a = Node::parse("2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2");
+    fn test_parse_whitespace() {
+        assert_eq!(Tree::parse("  1 1\n0 1 50\t100\n"), Tree::parse("1 1 0 1 50 100"));
+    }
 
-        assert_eq!(node_a.children.len(), 2);
-        assert_eq!(node_a.metadata, vec![1, 1, 2]);
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Tree::parse(""),
+            Err(ParseError { position: 0, offset: 0, kind: ErrorKind::Truncated(Field::ChildCount) })
+        );
+        assert_eq!(
+            Tree::parse("1 1 0 1 50"),
+            Err(ParseError { position: 5, offset: 10, kind: ErrorKind::Truncated(Field::Metadata) })
+        );
+        assert_eq!(
+            Tree::parse("1 1 0 x 50 100"),
+            Err(ParseError { position: 3, offset: 6, kind: ErrorKind::InvalidNumber(Field::MetadataCount, "x".to_string()) })
+        );
+        assert_eq!(
+            Tree::parse("-1 1 0"),
+            Err(ParseError { position: 0, offset: 0, kind: ErrorKind::InvalidNumber(Field::ChildCount, "-1".to_string()) })
+        );
+        assert_eq!(
+            Tree::parse("0 1 1 0 1 2"),
+            Err(ParseError { position: 3, offset: 6, kind: ErrorKind::TrailingInput(3) })
+        );
+    }
 
-        let node_b = node_a.children.get(0).unwrap();
+    #[test]
+    fn test_metadata_sum() {
+        let tree = Tree { nodes: vec![node(vec![1], vec![200]), node(vec![], vec![100, 50])] };
 
-        assert_eq!(node_b.children.len(), 0);
-        assert_eq!(node_b.metadata, vec![10, 11, 12]);
+        assert_eq!(tree.metadata_sum(), 350);
+    }
 
-        let node_c = node_a.children.get(1).unwrap();
+    #[test]
+    fn test_value() {
+        let tree = Tree::parse(EXAMPLE).unwrap();
 
-        assert_eq!(node_c.children.len(), 1);
-        assert_eq!(node_c.metadata, vec![2]);
+        assert_eq!(tree.values(), vec![66, 33, 0, 99]);
+        assert_eq!(tree.value(), 66);
 
-        let node_d = node_c.children.get(0).unwrap();
+        // entries that don't refer to a child count for nothing
+        assert_eq!(Tree::parse("1 3 0 1 7 0 -1 3").unwrap().value(), 0);
+    }
+
+    #[test]
+    fn test_metadata_sums() {
+        let tree = Tree::parse(EXAMPLE).unwrap();
 
-        assert_eq!(node_d.children.len(), 0);
-        assert_eq!(node_d.metadata, vec![99]);
+        assert_eq!(tree.metadata_sums(), vec![138, 33, 101, 99]);
     }
 
     #[test]
-    fn test_metadata_sum() {
-        let node_a = Node {
-            children: vec![
-                Node { children: vec![], metadata: vec![100, 50] }
-            ],
-            metadata: vec![200]
-        };
+    fn test_encode() {
+        let tree = Tree::parse(EXAMPLE).unwrap();
 
-        assert_eq!(node_a.metadata_sum(), 350);
+        assert_eq!(tree.encode(0), EXAMPLE);
+        assert_eq!(tree.encode(2), "1 1 0 1 99 2");
+        assert_eq!(Tree::parse(&tree.encode(0)), Ok(tree));
     }
 
     #[test]
-    fn test_value() {
-        let node_b = Node { children: vec![], metadata: vec![10, 11, 12] };
-        let node_d = Node { children: vec![], metadata: vec![99] };
+    fn test_encode_input() {
+        let tree = Tree::parse(include_str!("../input.txt")).unwrap();
 
-        assert_eq!(node_b.value(), 33);
-        assert_eq!(node_d.value(), 99);
+        assert_eq!(Tree::parse(&tree.encode(0)), Ok(tree));
+    }
 
-        let node_c = Node { children: vec![node_d], metadata: vec![2] };
+    #[test]
+    fn test_find() {
+        let tree = Tree::parse(EXAMPLE).unwrap();
 
-        assert_eq!(node_c.value(), 0);
+        assert_eq!(tree.find("root"), Ok(0));
+        assert_eq!(tree.find("root/1"), Ok(2));
+        assert_eq!(tree.find("root/1/0/"), Ok(3));
+        assert_eq!(tree.node(tree.find("root/0").unwrap()).metadata, vec![10, 11, 12]);
 
-        let node_a = Node { children: vec![node_b, node_c], metadata: vec![1, 1, 2] };
+        assert!(tree.find("1/0").is_err());
+        assert!(tree.find("root/x").is_err());
+        assert_eq!(tree.find("root/1/1"), Err("`1` in `root/1/1` is not a child, the node has 1".to_string()));
+    }
 
-        assert_eq!(node_a.value(), 66);
+    #[test]
+    fn test_to_pretty() {
+        let tree = Tree::parse(EXAMPLE).unwrap();
+
+        assert_eq!(
+            tree.to_pretty(),
+            "root: value 66, metadata sum 138, metadata [1, 1, 2]\n\
+             \x20 0: value 33, metadata sum 33, metadata [10, 11, 12]\n\
+             \x20 1: value 0, metadata sum 101, metadata [2]\n\
+             \x20   0: value 99, metadata sum 99, metadata [99]\n"
+        );
+    }
 
+    #[test]
+    fn test_deep() {
+        // a chain of a million nodes, each with one child and one metadata entry of 1
+        let depth = 1_000_000;
+        let mut input = "1 1 ".repeat(depth - 1);
+        input.push_str("0 1 1");
+        input.push_str(&" 1".repeat(depth - 1));
+
+        let tree = Tree::parse(&input).unwrap();
+
+        assert_eq!(tree.metadata_sums().len(), depth);
+        assert_eq!(tree.metadata_sum(), depth as i32);
+        assert_eq!(tree.value(), 1);
+        assert_eq!(Tree::parse(&tree.encode(0)), Ok(tree));
     }
 }
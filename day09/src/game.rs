@@ -0,0 +1,210 @@
+use std::cmp::Reverse;
+use std::fmt;
+use util::circular_vec::CircularVec;
+use util::progress::{Cancelled, Progress};
+
+/// How the game is played.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rules {
+    pub players: usize,
+    /// the value of the last marble, marbles are numbered from 1 up to and including this one
+    pub last_marble: u64,
+    /// marbles that are a multiple of this are kept instead of placed
+    pub scoring_multiple: u64,
+    /// how many marbles counter-clockwise the marble is that is removed when scoring
+    pub remove_offset: isize,
+}
+
+impl Rules {
+    /// The rules of the puzzle: every 23rd marble scores and removes the marble 7 marbles
+    /// counter-clockwise.
+    pub fn new(players: usize, last_marble: u64) -> Rules {
+        Rules { players, last_marble, scoring_multiple: 23, remove_offset: 7 }
+    }
+
+    /// Whether the game can be played by these rules. Every scoring turn takes a marble from the
+    /// circle, with a multiple of 1 the circle is empty after the first turn.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.players == 0 {
+            return Err("there have to be players".to_string());
+        }
+        if self.scoring_multiple < 2 {
+            return Err(format!("a scoring multiple of {} would empty the circle, it has to be at least 2", self.scoring_multiple));
+        }
+        Ok(())
+    }
+}
+
+/// What happened in one turn, players are numbered from 1.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Turn {
+    Placed { player: usize, marble: u64 },
+    /// The player kept `marble` and removed `removed`, scoring both.
+    Scored { player: usize, marble: u64, removed: u64 },
+}
+
+impl fmt::Display for Turn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Turn::Placed { player, marble }             => write!(f, "player {} places {}", player, marble),
+            Turn::Scored { player, marble, removed }    => write!(f, "player {} keeps {} and removes {}, scoring {}", player, marble, removed, marble + removed),
+        }
+    }
+}
+
+/// The outcome of a game: the score of every player, and every turn if logged.
+#[derive(Debug)]
+pub struct Game {
+    /// the score of player 1 first
+    pub scores: Vec<u64>,
+    pub log: Vec<Turn>,
+}
+
+/// Play the game by `rules`, which have to pass `Rules::validate`.
+pub fn play(rules: &Rules, log: bool, progress: &Progress) -> Result<Game, Cancelled> {
+    debug_assert_eq!(rules.validate(), Ok(()));
+
+    let mut circle = CircularVec::new();  // TODO: use a linked list
+    let mut scores = vec![0; rules.players];
+    let mut turns = Vec::new();
+
+    let mut current = 0;
+
+    circle.insert(0, 0);
+
+    progress.phase("marbles", Some(rules.last_marble));
+
+    for marble in 1..=rules.last_marble {
+        let player = ((marble - 1) % rules.players as u64) as usize;
+
+        let turn = if marble % rules.scoring_multiple != 0 {
+            current = circle.insert(current + 2, marble);
+
+            Turn::Placed { player: player + 1, marble }
+        } else {
+            let (index, removed) = circle.get_and_remove(current - rules.remove_offset);
+            current = index;
+
+            scores[player] += marble + removed;
+            Turn::Scored { player: player + 1, marble, removed }
+        };
+
+        if log {
+            turns.push(turn);
+        }
+        progress.update(marble)?;
+    }
+
+    Ok(Game { scores, log: turns })
+}
+
+impl Game {
+    /// The winning player and their score, the lowest numbered player if it's a tie.
+    pub fn winner(&self) -> (usize, u64) {
+        self.scores.iter()
+            .enumerate()
+            .max_by_key(|(player, score)| (**score, Reverse(*player)))
+            .map(|(player, score)| (player + 1, *score))
+            .expect("There are players")
+    }
+
+    /// Every player with their score, from high to low.
+    pub fn scoreboard(&self) -> String {
+        let mut players: Vec<(usize, u64)> = self.scores.iter()
+            .enumerate()
+            .map(|(player, score)| (player + 1, *score))
+            .collect();
+        players.sort_by_key(|(player, score)| (Reverse(*score), *player));
+
+        players.into_iter()
+            .enumerate()
+            .map(|(rank, (player, score))| format!("{:>4}. player {:<4} {:>12}\n", rank + 1, player, score))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn high_score(players: usize, last_marble: u64) -> u64 {
+        play(&Rules::new(players, last_marble), false, &Progress::silent()).unwrap().winner().1
+    }
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(high_score(9, 25), 32);
+        assert_eq!(high_score(10, 1618), 8317);
+        assert_eq!(high_score(13, 7999), 146_373);
+        assert_eq!(high_score(17, 1104), 2764);
+        assert_eq!(high_score(21, 6111), 54_718);
+        assert_eq!(high_score(30, 5807), 37305);
+    }
+
+    #[test]
+    fn test_log() {
+        let game = play(&Rules::new(9, 25), true, &Progress::silent()).unwrap();
+
+        assert_eq!(game.log.len(), 25);
+        assert_eq!(game.log[0], Turn::Placed { player: 1, marble: 1 });
+        assert_eq!(game.log[22], Turn::Scored { player: 5, marble: 23, removed: 9 });
+        assert_eq!(game.log[22].to_string(), "player 5 keeps 23 and removes 9, scoring 32");
+        assert_eq!(game.winner(), (5, 32));
+
+        assert!(play(&Rules::new(9, 25), false, &Progress::silent()).unwrap().log.is_empty());
+    }
+
+    #[test]
+    fn test_rules() {
+        // scoring every 5th marble and removing the marble right before the current one
+        let rules = Rules { players: 2, last_marble: 5, scoring_multiple: 5, remove_offset: 1 };
+        let game = play(&rules, true, &Progress::silent()).unwrap();
+
+        // when marble 5 is played, 0 is right before the current marble 4
+        assert_eq!(game.log[4], Turn::Scored { player: 1, marble: 5, removed: 0 });
+        assert_eq!(game.scores, vec![5, 0]);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(Rules::new(9, 25).validate(), Ok(()));
+        assert!(Rules::new(0, 25).validate().is_err());
+        assert!(Rules { scoring_multiple: 0, ..Rules::new(9, 25) }.validate().is_err());
+        assert_eq!(
+            Rules { scoring_multiple: 1, ..Rules::new(9, 5) }.validate(),
+            Err("a scoring multiple of 1 would empty the circle, it has to be at least 2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_scoring_every_other_marble() {
+        // the smallest multiple that is allowed: the circle goes back and forth between 1 and 2
+        let rules = Rules { scoring_multiple: 2, ..Rules::new(1, 6) };
+        let game = play(&rules, true, &Progress::silent()).unwrap();
+
+        assert_eq!(game.log.len(), 6);
+        // 2 removes 0, 4 removes 1 and 6 removes 3
+        assert_eq!(game.scores, vec![2 + 4 + 1 + 6 + 3]);
+    }
+
+    #[test]
+    fn test_large_remove_offset() {
+        let rules = Rules { remove_offset: 3_000_000_000, ..Rules::new(9, 25) };
+
+        assert!(play(&rules, false, &Progress::silent()).is_ok());
+    }
+
+    #[test]
+    fn test_scoreboard() {
+        let game = Game { scores: vec![10, 32, 0, 32], log: Vec::new() };
+
+        assert_eq!(game.winner(), (2, 32));
+        assert_eq!(
+            game.scoreboard(),
+            "   1. player 2              32\n   \
+                2. player 4              32\n   \
+                3. player 1              10\n   \
+                4. player 3               0\n"
+        );
+    }
+}
@@ -1,52 +1,94 @@
-use util::circular_vec::CircularVec;
-use std::collections::HashMap;
-use std::time::Instant;
+mod game;
+
+use crate::game::{play, Rules};
+use std::path::Path;
+use structopt::StructOpt;
+use util::params::Params;
+use util::progress::Progress;
+use util::runner::{Day, RunnerOpt};
+
+#[derive(StructOpt, Debug)]
+struct Opt {
+    /// Amount of players, overrides the parameter
+    #[structopt(long = "players")]
+    players: Option<usize>,
+
+    /// Value of the last marble, overrides the parameter
+    #[structopt(long = "marbles")]
+    marbles: Option<u64>,
+
+    /// Print every turn of the game of puzzle 1 and the scoreboard instead of solving the puzzles
+    #[structopt(long = "log")]
+    log: bool,
+
+    /// Print the scoreboard of the game of puzzle 1 instead of solving the puzzles
+    #[structopt(long = "scoreboard")]
+    scoreboard: bool,
+
+    #[structopt(flatten)]
+    runner: RunnerOpt,
+}
 
-/// Returns (winning player, winning score).
-fn play_the_game(players: i32, marbles: u64) -> (i32, u64) {
-    let mut list = CircularVec::new();  // TODO: use a linked list
-    let mut scores = HashMap::new();
+fn rules(params: &Params) -> Result<Rules, String> {
+    let rules = Rules {
+        scoring_multiple: params.get::<u64>("scoring_multiple")?,
+        remove_offset: params.get::<isize>("remove_offset")?,
+        ..Rules::new(params.get::<usize>("players")?, params.get::<u64>("marbles")?)
+    };
 
-    let mut curr_index = 0;
-    let mut curr_player = 0;
+    rules.validate()?;
+    Ok(rules)
+}
 
-    list.insert(0, 0);
+/// The winning score when the game goes on up to `factor` times the last marble.
+fn high_score(rules: &Rules, factor: u64, progress: &Progress) -> Result<u64, String> {
+    let last_marble = rules.last_marble.checked_mul(factor)
+        .ok_or_else(|| format!("{} times {} marbles is more than there can be", factor, rules.last_marble))?;
+    let rules = Rules { last_marble, ..rules.clone() };
 
-    let start = Instant::now();
+    let game = play(&rules, false, progress).map_err(|cancelled| cancelled.to_string())?;
+    Ok(game.winner().1)
+}
 
-    for marble in 1..marbles+1 {
-        if marble % 23 != 0 {
-            curr_index = list.insert(curr_index + 2, marble);
-        } else {
-            let (removed_index, removed_marble) = list.get_and_remove(curr_index - 7);
-            curr_index = removed_index;
+fn main() {
+    let mut opt = Opt::from_args();
 
-            let player_score = scores.entry(curr_player).or_insert(0);
-            *player_score += marble;
-            *player_score += removed_marble;
-        }
-        // println!("p: {}\tm: {}\ti: {}\tl: {:?}\ts: {:?}", curr_player, marble, curr_index, list, scores);
+    if let Some(players) = opt.players {
+        opt.runner.set.push(format!("players={}", players));
+    }
+    if let Some(marbles) = opt.marbles {
+        opt.runner.set.push(format!("marbles={}", marbles));
+    }
 
-        if marble % 100_000 == 0 {
-            println!("{}: {} of {}", start.elapsed().as_secs(), marble, marbles);
+    if opt.log || opt.scoreboard {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let file = opt.runner.input.clone()
+            .unwrap_or_else(|| dir.join("input.txt"));
+
+        // the input only decides which parameters apply
+        let input = std::fs::read_to_string(&file).unwrap_or_default();
+        let params = opt.runner.param_file(dir)
+            .unwrap_or_else(|e| panic!("{}", e))
+            .for_input(&input);
+
+        let rules = rules(&params).unwrap_or_else(|e| {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        });
+        let game = play(&rules, opt.log, &Progress::silent())
+            .expect("The game is never cancelled");
+
+        for turn in &game.log {
+            println!("{}", turn);
         }
-
-        curr_player = (curr_player + 1) % players;
+        print!("{}", game.scoreboard());
+        return;
     }
 
-    let (player, score) = scores.iter()
-        .max_by_key(|(_, score)| *score)
-        .unwrap();
-
-    println!("Play time: {}", start.elapsed().as_secs());
-
-    // players is zero-indexed!
-    (player + 1, *score)
-}
-
-fn main() {
-    println!("Puzzle 1: {}", play_the_game(405, 70_953).1);
-    println!("Puzzle 2: {}", play_the_game(405, 70_953 * 100).1);
+    Day::try_with_params(env!("CARGO_MANIFEST_DIR"), |_, params| rules(params))
+        .try_puzzle_with_progress(|rules, progress| high_score(rules, 1, progress))
+        .try_puzzle_with_progress(|rules, progress| high_score(rules, 100, progress))
+        .run_with(opt.runner);
 }
 
 #[cfg(test)]
@@ -54,32 +96,16 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_game_1() {
-        assert_eq!(play_the_game(9, 25).1, 32);
-    }
-
-    #[test]
-    fn test_game_2() {
-        assert_eq!(play_the_game(10, 1618).1, 8317);
-    }
-
-    #[test]
-    fn test_game_3() {
-        assert_eq!(play_the_game(13, 7999).1, 146_373);
-    }
+    fn test_high_score() {
+        let rules = Rules::new(10, 1618);
 
-    #[test]
-    fn test_game_4() {
-        assert_eq!(play_the_game(17, 1104).1, 2764);
-    }
+        assert_eq!(high_score(&rules, 1, &Progress::silent()), Ok(8317));
 
-    #[test]
-    fn test_game_5() {
-        assert_eq!(play_the_game(21, 6111).1, 54_718);
-    }
+        let rules = Rules::new(10, u64::MAX / 10);
 
-    #[test]
-    fn test_game_6() {
-        assert_eq!(play_the_game(30, 5807).1, 37305);
+        assert_eq!(
+            high_score(&rules, 100, &Progress::silent()),
+            Err(format!("100 times {} marbles is more than there can be", u64::MAX / 10))
+        );
     }
 }
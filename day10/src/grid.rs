@@ -1,12 +1,11 @@
 use crate::point::Point;
 use crate::vec2::Vec2;
 
-use std::fmt::{Display, Formatter, Result};
 use nom::*;
 use nom::types::CompleteStr;
 use util::nom_utils::parse_i64;
 
-/// A grid holding a collection of points. Supports 'nice' visuals and 'advanced' zooming.
+/// A grid holding a collection of points moving through the sky.
 pub struct Grid {
     data: Vec<Point<i64>>
 }
@@ -35,77 +34,77 @@ impl Grid {
         Grid { data }
     }
 
-    pub fn step(&mut self, steps: i32) -> i32 {
-        for point in &mut self.data {
-            point.step(steps);
-        }
-        steps
-    }
+    /// The lowest and highest corner of the area the points cover after `steps` steps.
+    fn bounds_at(&self, steps: i64) -> (Vec2<i64>, Vec2<i64>) {
+        let positions: Vec<Vec2<i64>> = self.data.iter().map(|p| p.at(steps)).collect();
 
-    pub fn step_into_visibility(&mut self) -> i32 {
-        let mut steps_taken = 0;
-
-        while !self.is_viewable() {
-            self.step(1);
-            steps_taken += 1;
-        }
-        steps_taken
+        let low = Vec2::new(
+            positions.iter().map(|pos| pos.x).min().unwrap_or(0),
+            positions.iter().map(|pos| pos.y).min().unwrap_or(0),
+        );
+        let high = Vec2::new(
+            positions.iter().map(|pos| pos.x).max().unwrap_or(0),
+            positions.iter().map(|pos| pos.y).max().unwrap_or(0),
+        );
+        (low, high)
     }
 
-    fn lower_bound(&self) -> Vec2<i64> {
-        let x = self.data.iter().map(|p| p.pos.x).min().unwrap_or(0);
-        let y = self.data.iter().map(|p| p.pos.y).min().unwrap_or(0);
+    /// The width and height of the area the points cover after `steps` steps.
+    pub fn size_at(&self, steps: i64) -> (i64, i64) {
+        let (low, high) = self.bounds_at(steps);
 
-        Vec2::new(x, y)
+        (high.x - low.x + 1, high.y - low.y + 1)
     }
 
-    fn higher_bound(&self) -> Vec2<i64> {
-        let x = self.data.iter().map(|p| p.pos.x).max().unwrap_or(0);
-        let y = self.data.iter().map(|p| p.pos.y).max().unwrap_or(0);
+    fn area_at(&self, steps: i64) -> i64 {
+        let (width, height) = self.size_at(steps);
 
-        Vec2::new(x, y)
+        width * height
     }
 
-    fn is_viewable(&self) -> bool {
-        let low = self.lower_bound();
-        let high = self.higher_bound();
+    /// The step at which the points are closest together, where they form the message. The
+    /// width and height first shrink and then grow, so does the area: double the steps until the
+    /// area grows, then binary search for where it stops shrinking. O(n log steps).
+    pub fn converge(&self) -> i64 {
+        let mut high = 1;
+        while self.area_at(high * 2) < self.area_at(high) {
+            high *= 2;
+        }
+        high *= 2;
+
+        let mut low = 0;
+        while low < high {
+            let mid = low + (high - low) / 2;
 
-        high.x - low.x < 250 && high.y - low.y < 250
+            if self.area_at(mid + 1) >= self.area_at(mid) {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        low
     }
 
-    fn contains_point(&self, x: i64, y: i64) -> bool {
-        self.data.iter()
-            .map(|p| &p.pos)
-            .find(|p| **p == (x, y))
-            .is_some()
-    }
+    /// The points after `steps` steps, drawn with `#` and `.` within the area they cover. That
+    /// area can be huge, check `size_at` first.
+    pub fn render_at(&self, steps: i64) -> String {
+        let (low, high) = self.bounds_at(steps);
+        let width = (high.x - low.x + 1) as usize;
+        let height = (high.y - low.y + 1) as usize;
 
-}
+        let mut rows = vec![vec!['.'; width]; height];
 
-impl Display for Grid {
-    fn fmt(&self, f: &mut Formatter) -> Result {
-        write!(f, "*\n")?;
-
-        if !self.is_viewable() {
-            write!(f, "| ... to big to display ...\n")?;
-        } else {
-            let low = self.lower_bound();
-            let high = self.higher_bound();
-
-            for y in low.y..high.y+1 {
-                write!(f, "| ")?;
-
-                for x in low.x..high.x+1 {
-                    if self.contains_point(x, y) {
-                        write!(f, "#")?;
-                    } else {
-                        write!(f, ".")?;
-                    }
-                }
-                write!(f, "\n")?;
-            }
+        for point in &self.data {
+            let mut pos = point.at(steps);
+            pos.sub(&low);
+
+            rows[pos.y as usize][pos.x as usize] = '#';
         }
-        write!(f, "*")
+
+        rows.into_iter()
+            .map(|row| row.into_iter().collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
     }
 }
 
@@ -125,4 +124,34 @@ mod test {
             vec!(Point::new(9, 1, 0, 2), Point::new(7, 0, -1, 0))
         );
     }
+
+    #[test]
+    fn test_converge() {
+        let content = include_str!("../example_input.txt");
+        let grid = Grid::from_vec(&content.lines().collect());
+
+        assert_eq!(grid.converge(), 3);
+        assert_eq!(
+            grid.render_at(3),
+            "#...#..###\n\
+             #...#...#.\n\
+             #...#...#.\n\
+             #####...#.\n\
+             #...#...#.\n\
+             #...#...#.\n\
+             #...#...#.\n\
+             #...#..###"
+        );
+    }
+
+    #[test]
+    fn test_converge_in_the_past() {
+        // the points were closest together 100 steps ago, so it's now
+        let grid = Grid::from_vec(&vec![
+            "position=< 100,  0> velocity=< 1,  0>",
+            "position=<-100,  0> velocity=<-1,  0>",
+        ]);
+
+        assert_eq!(grid.converge(), 0);
+    }
 }
\ No newline at end of file
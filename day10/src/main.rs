@@ -1,62 +1,112 @@
 mod grid;
+mod ocr;
 mod point;
 mod vec2;
 
-use std::io;
 use crate::grid::Grid;
-
-use std::path::PathBuf;
+use std::path::Path;
 use structopt::StructOpt;
+use util::runner::{Day, RunnerOpt};
 
 #[derive(StructOpt, Debug)]
 struct Opt {
-    /// Input file with the points.
-    #[structopt(parse(from_os_str))]
-    input: PathBuf,
+    /// Print the points at the step they form the message instead of solving the puzzles
+    #[structopt(long = "show")]
+    show: bool,
+
+    #[structopt(flatten)]
+    runner: RunnerOpt,
+}
+
+fn parse_input(input: &str) -> Vec<&str> {
+    input.lines()
+        .filter(|line| !line.is_empty())
+        .collect()
 }
 
 fn main() {
     let opt = Opt::from_args();
 
-    let content = std::fs::read_to_string(&opt.input)
-        .expect("Could not read input file");
+    if opt.show {
+        let file = opt.runner.input
+            .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("input.txt"));
 
-    let input = content.lines()
-        .filter(|line| line.len() > 0)
-        .collect();
+        let input = std::fs::read_to_string(&file)
+            .expect("Could not read input file");
 
-    let mut grid = Grid::from_vec(&input);
-    let mut steps = 0;
+        let grid = Grid::from_vec(&parse_input(&input));
+        let steps = grid.converge();
 
-    loop {
-        println!("");
-        println!("{}", grid);
-        println!("");
-        println!("{} steps from initial state", steps);
-        println!("");
-        println!("- #\tStep #");
-        println!("- s\tStep into visibility");
-        println!("- q\tQuit");
-        println!("");
+        match message_at(&grid, steps) {
+            Ok(message) => println!("{}\nafter {} steps", message, steps),
+            Err(e)      => {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
 
-        let mut input = String::new();
+    Day::new(env!("CARGO_MANIFEST_DIR"), parse_input)
+        .try_puzzle(puzzle_1)
+        .puzzle(puzzle_2)
+        .run_with(opt.runner);
+}
 
-        io::stdin().read_line(&mut input)
-            .expect("Could not read input");
+/// A message is a single line of letters, a lot narrower than this.
+const MAX_WIDTH: i64 = 1000;
 
-        if let Ok(n) = input.trim().parse::<i32>() {
-            println!("Stepping {}", n);
-            steps += grid.step(n);
-            continue;
-        }
-        if input.contains("s") {
-            println!("ENHANCE!");
-            steps += grid.step_into_visibility();
-            continue;
-        }
-        if input.contains("q") {
-            println!("Bye");
-            break;
-        }
+/// The points after `steps` steps, if they are as high as a line of letters and not too wide to
+/// draw. Points that never form a message can still cover a huge area when closest together.
+fn message_at(grid: &Grid, steps: i64) -> Result<String, String> {
+    let (width, height) = grid.size_at(steps);
+
+    if height != ocr::HEIGHT as i64 {
+        return Err(format!("after {} steps the points are {} rows high, letters are {}", steps, height, ocr::HEIGHT));
+    }
+    if width > MAX_WIDTH {
+        return Err(format!("after {} steps the points are {} columns wide, more than a message", steps, width));
+    }
+    Ok(grid.render_at(steps))
+}
+
+fn puzzle_1(input: &Vec<&str>) -> Result<String, String> {
+    let grid = Grid::from_vec(input);
+
+    ocr::read(&message_at(&grid, grid.converge())?)
+}
+
+fn puzzle_2(input: &Vec<&str>) -> i64 {
+    Grid::from_vec(input).converge()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_puzzle_1() {
+        let input = include_str!("../input.txt");
+
+        assert_eq!(puzzle_1(&parse_input(input)), Ok("HKJFAKAF".to_string()));
+    }
+
+    #[test]
+    fn test_puzzle_1_no_message() {
+        // the points only move apart, they are closest together right away
+        let apart = vec!["position=<0, 0> velocity=<-1, -1>", "position=<50000, 50000> velocity=<1, 1>"];
+
+        assert_eq!(puzzle_1(&apart), Err("after 0 steps the points are 50001 rows high, letters are 10".to_string()));
+
+        let wide = vec!["position=<0, 0> velocity=<0, 0>", "position=<2000000, 9> velocity=<0, 0>"];
+
+        assert_eq!(puzzle_1(&wide), Err("after 0 steps the points are 2000001 columns wide, more than a message".to_string()));
+    }
+
+    #[test]
+    fn test_puzzle_2() {
+        let example = include_str!("../example_input.txt");
+
+        assert_eq!(puzzle_2(&parse_input(example)), 3);
     }
 }
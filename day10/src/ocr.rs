@@ -0,0 +1,114 @@
+/// The height of a letter.
+pub const HEIGHT: usize = 10;
+
+/// The letters of the messages in the sky, 6 wide and 10 high.
+const FONT: &[(char, [&str; 10])] = &[
+    ('A', ["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', ["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', [".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', [".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', ["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', ["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', ["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', ["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', ["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', ["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', ["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', ["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', ["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+/// Read a message drawn with `#` and `.`, one line per row. Letters are separated by empty
+/// columns and compared without the empty columns around them, so the spacing doesn't matter.
+pub fn read(message: &str) -> Result<String, String> {
+    let rows: Vec<Vec<bool>> = message.lines()
+        .map(|line| line.chars().map(|c| c == '#').collect())
+        .collect();
+
+    if rows.len() != HEIGHT {
+        return Err(format!("the message is {} rows high, letters are {}", rows.len(), HEIGHT));
+    }
+
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let lit = |x: usize| rows.iter().any(|row| row.get(x) == Some(&true));
+
+    let mut text = String::new();
+    let mut x = 0;
+
+    while x < width {
+        if !lit(x) {
+            x += 1;
+            continue;
+        }
+
+        let start = x;
+        while x < width && lit(x) {
+            x += 1;
+        }
+
+        let glyph: Vec<String> = rows.iter()
+            .map(|row| (start..x).map(|x| if row.get(x) == Some(&true) { '#' } else { '.' }).collect())
+            .collect();
+
+        let letter = FONT.iter()
+            .find(|(_, font)| trim(font) == glyph)
+            .map(|(letter, _)| *letter)
+            .ok_or_else(|| format!("unknown letter at column {}:\n{}", start, glyph.join("\n")))?;
+
+        text.push(letter);
+    }
+    Ok(text)
+}
+
+/// The rows of a glyph without the empty columns on either side.
+fn trim(glyph: &[&str]) -> Vec<String> {
+    let lit = |x: usize| glyph.iter().any(|row| row.as_bytes()[x] == b'#');
+
+    let first = (0..6).find(|x| lit(*x)).unwrap_or(0);
+    let last = (0..6).rev().find(|x| lit(*x)).unwrap_or(0);
+
+    glyph.iter()
+        .map(|row| row[first..=last].to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Draw `text` like the sky does: letters 6 wide with `gap` empty columns in between.
+    fn draw(text: &str, gap: usize) -> String {
+        (0..10)
+            .map(|y| {
+                let row: Vec<&str> = text.chars()
+                    .map(|c| FONT.iter().find(|(letter, _)| *letter == c).unwrap().1[y])
+                    .collect();
+                row.join(&".".repeat(gap))
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_read() {
+        let alphabet: String = FONT.iter().map(|(letter, _)| *letter).collect();
+
+        assert_eq!(read(&draw(&alphabet, 2)), Ok(alphabet));
+    }
+
+    #[test]
+    fn test_read_spacing() {
+        assert_eq!(read(&draw("JAZZ", 5)), Ok("JAZZ".to_string()));
+        assert_eq!(read(&format!("..{}", draw("HX", 2).replace('\n', "\n.."))), Ok("HX".to_string()));
+    }
+
+    #[test]
+    fn test_read_errors() {
+        assert!(read("#\n#").is_err());
+
+        let unknown = ["#.#"; 10].join("\n");
+        assert!(read(&unknown).unwrap_err().starts_with("unknown letter at column 0"));
+    }
+}
@@ -8,23 +8,17 @@ pub struct Point<T> {
 }
 
 impl<T> Point<T>
-    where T: std::marker::Copy + std::ops::AddAssign + std::ops::SubAssign
+    where T: std::marker::Copy + std::ops::AddAssign + std::ops::SubAssign + std::ops::Mul<Output = T>
 {
     pub fn new(px: T, py: T, vx: T, vy: T) -> Point<T> {
         Point { pos: Vec2::new(px, py), vel: Vec2::new(vx, vy) }
     }
 
-    /// Update the position of this point by one 'step'.
-    pub fn step(&mut self, steps: i32) {
-        if steps > 0 {
-            for _ in 0..steps {
-                self.pos.add(&self.vel);
-            }
-        } else {
-            for _ in 0..steps.abs() {
-                self.pos.sub(&self.vel);
-            }
-        }
+    /// The position of this point after `steps` steps, negative steps go back in time.
+    pub fn at(&self, steps: T) -> Vec2<T> {
+        let mut pos = self.vel.scale(steps);
+        pos.add(&self.pos);
+        pos
     }
 }
 
@@ -33,19 +27,12 @@ mod test {
     use super::*;
 
     #[test]
-    fn test_point_i32_step() {
-        let mut p1 = Point::new(0, 0, 1, 5);
+    fn test_point_i32_at() {
+        let p1 = Point::new(0, 0, 1, 5);
 
-        p1.step(1);
-        assert_eq!(p1.pos, (1, 5));
-
-        p1.step(1);
-        assert_eq!(p1.pos, (2, 10));
-
-        p1.step(3);
-        assert_eq!(p1.pos, (5, 25));
-
-        p1.step(-1);
-        assert_eq!(p1.pos, (4, 20));
+        assert_eq!(p1.at(0), (0, 0));
+        assert_eq!(p1.at(1), (1, 5));
+        assert_eq!(p1.at(5), (5, 25));
+        assert_eq!(p1.at(-1), (-1, -5));
     }
 }
@@ -1,7 +1,7 @@
 use std::fmt::{Display, Formatter, Result};
 
 /// A 2 dimensional vector.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Vec2<T> {
     pub x: T,
     pub y: T,
@@ -23,6 +23,12 @@ impl<T> Vec2<T>
         self.x -= other.x;
         self.y -= other.y;
     }
+
+    pub fn scale(&self, factor: T) -> Vec2<T>
+        where T: std::ops::Mul<Output = T>
+    {
+        Vec2 { x: self.x * factor, y: self.y * factor }
+    }
 }
 
 impl<T> PartialEq<(T, T)> for Vec2<T> 
@@ -61,5 +67,7 @@ mod test {
 
         v2.sub(&v1);
         assert_eq!(v2, (-15, -9));
+
+        assert_eq!(v2.scale(-2), (30, 18));
     }
 }
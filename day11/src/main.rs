@@ -1,12 +1,18 @@
-use std::time::Instant;
 use rayon::prelude::*;
 use util::grid::Grid;
-
-const INPUT: isize = 8141;
+use util::runner::Day;
 
 fn main() {
-    println!("Puzzle 1: {:?}", puzzle_1(INPUT));
-    println!("Puzzle 2: {:?}", puzzle_2(INPUT));
+    Day::with_params(env!("CARGO_MANIFEST_DIR"), |_, params| params.require::<isize>("serial_number"))
+        .puzzle(|serial_number| {
+            let (x, y) = puzzle_1(*serial_number);
+            format!("{},{}", x, y)
+        })
+        .puzzle(|serial_number| {
+            let (x, y, size) = puzzle_2(*serial_number);
+            format!("{},{},{}", x, y, size)
+        })
+        .run();
 }
 
 fn create_power_grid(serial_number: isize) -> Grid<isize> {
@@ -32,21 +38,15 @@ fn puzzle_1(serial_number: isize) -> (usize, usize) {
 fn puzzle_2(serial_number: isize) -> (usize, usize, usize) {
     let grid = create_power_grid(serial_number);
 
-    let start = Instant::now();
-
     let (x, y, _, size) = (1..300usize)
         .into_par_iter()
-        // .inspect(|i| println!("{}: start {}", start.elapsed().as_secs(), i))
         .map(|size| {
             let (x, y, total_power) = grid.find_max_rect(size, size);
             (x, y, total_power, size)
         })
-        // .inspect(|(_, _, _, size)| println!("{}: {} done", start.elapsed().as_secs(), size))
         .max_by_key(|(_, _, total_power, _)| *total_power)
         .unwrap();
 
-    println!("Computation time: {} secs", start.elapsed().as_secs());
-
     (x, y, size)
 }
 
@@ -1,9 +1,10 @@
 mod rule;
 mod row;
 
-use std::time::Instant;
 use crate::row::Row;
 use crate::rule::Rule;
+use util::progress::{Cancelled, Progress};
+use util::runner::Day;
 
 fn parse_input(input: &str) -> (Row, Vec<Rule>) {
     let lines: Vec<&str> = input.lines().collect();
@@ -25,11 +26,10 @@ fn parse_input(input: &str) -> (Row, Vec<Rule>) {
 }
 
 fn main() {
-    let input = include_str!("../input.txt")
-        .trim();
-
-    println!("Puzzle 1: {}", puzzle_1(&input));
-    println!("Puzzle 2: {}", puzzle_2(&input));
+    Day::new(env!("CARGO_MANIFEST_DIR"), |input| input.trim())
+        .puzzle(|input| puzzle_1(input))
+        .puzzle_with_progress(|input, progress| puzzle_2(input, progress))
+        .run();
 }
 
 fn puzzle_1(input: &str) -> isize {
@@ -41,22 +41,19 @@ fn puzzle_1(input: &str) -> isize {
     row.sum_of_pots_alive()
 }
 
-fn puzzle_2(input: &str) -> isize {
-    let (mut row, rules) = parse_input(&input);
+fn puzzle_2(input: &str, progress: &Progress) -> Result<isize, Cancelled> {
+    let (mut row, rules) = parse_input(input);
 
-    let start = Instant::now();
+    let generations = 50_000_000_000u64;
+    progress.phase("generations", Some(generations));
 
-    for i in 0..50_000_000_000usize {
+    for i in 1..generations+1 {
         row = row.next_generation(&rules);
 
-        if i % 10_000 == 0 {
-            println!("{} sec: at {} generations", start.elapsed().as_secs(), i);
-        }
+        progress.update(i)?;
     }
 
-    println!("Computation time: {} secs", start.elapsed().as_secs());
-
-    row.sum_of_pots_alive()
+    Ok(row.sum_of_pots_alive())
 }
 
 #[cfg(test)]
@@ -5,12 +5,19 @@ mod world;
 
 use crate::world::World;
 use crate::cart::Pos;
+use util::runner::Day;
 
 fn main() {
-    let input = include_str!("../input.txt");
-
-    println!("Puzzle 1: {}", puzzle_1(&input));
-    println!("Puzzle 2: {}", puzzle_2(&input));
+    Day::new(env!("CARGO_MANIFEST_DIR"), |input| input)
+        .puzzle(|input| {
+            let pos = puzzle_1(input);
+            format!("{},{}", pos.x, pos.y)
+        })
+        .puzzle(|input| {
+            let pos = puzzle_2(input);
+            format!("{},{}", pos.x, pos.y)
+        })
+        .run();
 }
 
 fn puzzle_1(input: &str) -> Pos {
@@ -1,3 +1,5 @@
+use util::runner::Day;
+
 struct RecipeBoard {
     list: Vec<u8>,
     elf1: usize,
@@ -61,12 +63,26 @@ impl RecipeBoard {
     }
 }
 
-const INPUT: usize = 633601;
-const INPUT_ARR: [u8; 6] = [6, 3, 3, 6, 0, 1];
+/// The puzzle input is a number, but its digits matter for puzzle 2 (including leading zeros).
+fn parse_digits(input: &str) -> Vec<u8> {
+    input.trim()
+        .chars()
+        .map(|c| c.to_digit(10).expect("Input should only contain digits") as u8)
+        .collect()
+}
 
 fn main() {
-    println!("Puzzle 1: {:?}", puzzle_1(INPUT));
-    println!("Puzzle 2: {:?}", puzzle_2(&INPUT_ARR.to_vec()));
+    Day::with_params(env!("CARGO_MANIFEST_DIR"), |_, params| params.require::<String>("recipes"))
+        .puzzle(|recipes| {
+            let experiments = recipes.parse::<usize>()
+                .expect("Input should be a number");
+
+            puzzle_1(experiments).iter()
+                .map(|recipe| recipe.to_string())
+                .collect::<String>()
+        })
+        .puzzle(|recipes| puzzle_2(&parse_digits(recipes)))
+        .run();
 }
 
 fn puzzle_1(experiments: usize) -> Vec<u8> {
@@ -100,4 +116,10 @@ mod tests {
         assert_eq!(puzzle_2(&vec![9, 2, 5, 1, 0]), 18);
         assert_eq!(puzzle_2(&vec![5, 9, 4, 1, 4]), 2018);
     }
+
+    #[test]
+    fn test_parse_digits() {
+        assert_eq!(parse_digits("633601"), vec![6, 3, 3, 6, 0, 1]);
+        assert_eq!(parse_digits("01245\n"), vec![0, 1, 2, 4, 5]);
+    }
 }
@@ -1,5 +1,6 @@
 use util::grid::Grid;
-use std::time::Instant;
+use util::progress::{Cancelled, Progress};
+use util::runner::Day;
 
 const OPEN_GROUND: char = '.';
 const TREES: char = '|';
@@ -69,30 +70,31 @@ fn resource_value(grid: &Grid<char>) -> usize {
 }
 
 fn main() {
-    let input = include_str!("../input.txt");
-
-    let start = Instant::now();
-
-    let mut grid = parse_input(&input);
-    let mut steps = 0;
+    Day::new(env!("CARGO_MANIFEST_DIR"), parse_input)
+        .puzzle(puzzle_1)
+        .puzzle_with_progress(puzzle_2)
+        .run();
+}
 
-    grid = evolve_times(&grid, 10);
-    steps += 10;
+/// Resource value after 10 minutes.
+fn puzzle_1(grid: &Grid<char>) -> usize {
+    resource_value(&evolve_times(grid, 10))
+}
 
-    println!("Puzzle 1: after 10 minutes: {}", resource_value(&grid));
+/// Resource value after 1000s years.
+fn puzzle_2(grid: &Grid<char>, progress: &Progress) -> Result<usize, Cancelled> {
+    let minutes = 1_000_000_000u64;
+    progress.phase("minutes", Some(minutes));
 
-    // align to 10.000
-    grid = evolve_times(&grid, 10_000 - steps);
-    steps = 10_000;
-    println!("{} seconds, {} steps", start.elapsed().as_secs(), steps);
+    let mut grid = evolve(grid);
+    progress.update(1)?;
 
-    while steps < 1_000_000_000usize {
-        grid = evolve_times(&grid, 10_000);
-        steps += 10_000;
-        println!("{} seconds, {} steps", start.elapsed().as_secs(), steps);
+    for minute in 2..minutes+1 {
+        grid = evolve(&grid);
+        progress.update(minute)?;
     }
 
-    println!("Puzzle 2: after 1000s years: {}", resource_value(&grid));
+    Ok(resource_value(&grid))
 }
 
 #[cfg(test)]
@@ -4,6 +4,8 @@ mod nanobot;
 use crate::vec3::Vec3;
 use crate::nanobot::Nanobot;
 use std::ops::Range;
+use util::progress::{Cancelled, Progress};
+use util::runner::Day;
 
 fn parse_input(input: &str) -> Result<Vec<Nanobot>, &'static str> {
     let mut list = Vec::new();
@@ -17,12 +19,10 @@ fn parse_input(input: &str) -> Result<Vec<Nanobot>, &'static str> {
 }
 
 fn main() {
-    let input = include_str!("../input.txt");
-    let bots = parse_input(&input)
-        .expect("Could not parse input");
-
-    println!("Puzzle 1: {}", puzzle_1(&bots));
-    println!("Puzzle 2: {}", puzzle_2(&bots));
+    Day::new(env!("CARGO_MANIFEST_DIR"), |input| parse_input(input).expect("Could not parse input"))
+        .puzzle(puzzle_1)
+        .puzzle_with_progress(puzzle_2)
+        .run();
 }
 
 /// Finds the nanobot with the largest signal radius, returns how many other nanobots it can reach.
@@ -44,10 +44,10 @@ struct Bounds {
 }
 
 /// Find the bounds of hotspot of size `square` which is most likely to contain most bots within reach.
-fn find_most_likely_square(bots: &Vec<Nanobot>, bounds: &Bounds, square: usize) -> Bounds {
+fn find_most_likely_square(bots: &Vec<Nanobot>, bounds: &Bounds, square: usize, progress: &Progress) -> Bounds {
     let isquare = square as isize;
 
-    println!("Hotspot {}, given {:?}", square, bounds);
+    progress.log(&format!("Hotspot {}, given {:?}", square, bounds));
 
     let mut max_squares = Vec::new();
     let mut max_count = 0;
@@ -83,7 +83,7 @@ fn find_most_likely_square(bots: &Vec<Nanobot>, bounds: &Bounds, square: usize)
         }
     }
 
-    println!("Found {} squares w count: {}", max_squares.len(), max_count);
+    progress.log(&format!("Found {} squares w count: {}", max_squares.len(), max_count));
 
     let max_square = max_squares.iter()
         .min_by_key(|sq| Vec3::new(sq.x + isquare/2, sq.y + isquare/2, sq.z + isquare/2).distance_origin())
@@ -97,7 +97,7 @@ fn find_most_likely_square(bots: &Vec<Nanobot>, bounds: &Bounds, square: usize)
 }
 
 /// Finds the position that is in range of the most nanobots and is closest to (0, 0, 0).
-fn puzzle_2(bots: &Vec<Nanobot>) -> usize {
+fn puzzle_2(bots: &Vec<Nanobot>, progress: &Progress) -> Result<usize, Cancelled> {
     // determine bounds of space to search
     let min_x = bots.iter().map(|bot| bot.pos.x).min().unwrap();
     let max_x = bots.iter().map(|bot| bot.pos.x).max().unwrap();
@@ -109,34 +109,20 @@ fn puzzle_2(bots: &Vec<Nanobot>) -> usize {
     let max_z = bots.iter().map(|bot| bot.pos.z).max().unwrap();
 
     let bounds = Bounds { x: (min_x..max_x), y: (min_y..max_y), z: (min_z..max_z) };
-    println!("Total space to search: {:?}", bounds);
+    progress.log(&format!("Total space to search: {:?}", bounds));
 
     // reduce bounds to -hopefully- interesting hotspot
-    let bounds = find_most_likely_square(&bots, &bounds, 134_217_728);
-    let bounds = find_most_likely_square(&bots, &bounds, 67_108_864);
-    let bounds = find_most_likely_square(&bots, &bounds, 33_554_432);
-    let bounds = find_most_likely_square(&bots, &bounds, 16_777_216);
-    let bounds = find_most_likely_square(&bots, &bounds, 8_388_608);
-    let bounds = find_most_likely_square(&bots, &bounds, 4_194_304);
-    let bounds = find_most_likely_square(&bots, &bounds, 2_097_152);
-    let bounds = find_most_likely_square(&bots, &bounds, 1_048_576);
-    let bounds = find_most_likely_square(&bots, &bounds, 524_288);
-    let bounds = find_most_likely_square(&bots, &bounds, 262_144);
-    let bounds = find_most_likely_square(&bots, &bounds, 131_072);
-    let bounds = find_most_likely_square(&bots, &bounds, 65_536);
-    let bounds = find_most_likely_square(&bots, &bounds, 32_768);
-    let bounds = find_most_likely_square(&bots, &bounds, 16_384);
-    let bounds = find_most_likely_square(&bots, &bounds, 8_192);
-    let bounds = find_most_likely_square(&bots, &bounds, 4_096);
-    let bounds = find_most_likely_square(&bots, &bounds, 2_048);
-    let bounds = find_most_likely_square(&bots, &bounds, 1_024);
-    let bounds = find_most_likely_square(&bots, &bounds, 512);
-    let bounds = find_most_likely_square(&bots, &bounds, 256);
-    let bounds = find_most_likely_square(&bots, &bounds, 128);
-    let bounds = find_most_likely_square(&bots, &bounds, 64);
-    let bounds = find_most_likely_square(&bots, &bounds, 32);
-    let bounds = find_most_likely_square(&bots, &bounds, 16);
-    let bounds = find_most_likely_square(&bots, &bounds, 8);
+    // squares of 2^27 = 134_217_728 down to 2^3 = 8
+    let levels = 25;
+    progress.phase("refining", Some(levels));
+
+    let mut bounds = bounds;
+    for level in 0..levels {
+        let square = 1usize << (27 - level);
+
+        bounds = find_most_likely_square(bots, &bounds, square, progress);
+        progress.update(level + 1)?;
+    }
 
     // naive search attempt
     let mut positions = Vec::new();
@@ -161,14 +147,14 @@ fn puzzle_2(bots: &Vec<Nanobot>) -> usize {
         }
     }
 
-    println!("Found {} positions w count: {}", positions.len(), max_count);
+    progress.log(&format!("Found {} positions w count: {}", positions.len(), max_count));
 
     let mut max_pos = positions.iter()
         .min_by_key(|pos| pos.distance_origin())
         .unwrap()
         .clone();
 
-    println!("Position: {:?}, w count: {}", max_pos, max_count);
+    progress.log(&format!("Position: {:?}, w count: {}", max_pos, max_count));
 
     // previous result is not great, try to step towards a better solution...
     let mut steps = -1;
@@ -202,11 +188,11 @@ fn puzzle_2(bots: &Vec<Nanobot>) -> usize {
 
         break;
     }
-    println!("Took {} extra steps", steps);
+    progress.log(&format!("Took {} extra steps", steps));
 
-    println!("Position: {:?}, w count: {}", max_pos, max_count);
+    progress.log(&format!("Position: {:?}, w count: {}", max_pos, max_count));
 
-    max_pos.distance_origin()
+    Ok(max_pos.distance_origin())
 }
 
 #[cfg(test)]
@@ -228,6 +214,6 @@ mod test {
         let bots = parse_input(&example)
             .expect("Could not parse input");
 
-        assert_eq!(puzzle_2(&bots), 36);
+        assert_eq!(puzzle_2(&bots, &Progress::silent()), Ok(36));
     }
 }
@@ -1,6 +1,7 @@
 mod vec4;
 
 use crate::vec4::Vec4;
+use util::runner::Day;
 
 type Point = Vec4;
 type Constellation<'a> = Vec<&'a Point>;
@@ -20,11 +21,9 @@ fn parse_input(input: &str) -> Result<Vec<Point>, &'static str> {
 }
 
 fn main() {
-    let input = include_str!("../input.txt");
-    let points = parse_input(&input)
-        .expect("Could not parse input");
-
-    println!("Puzzle 1: {}", puzzle_1(&points));
+    Day::new(env!("CARGO_MANIFEST_DIR"), |input| parse_input(input).expect("Could not parse input"))
+        .puzzle(puzzle_1)
+        .run();
 }
 
 fn is_part_of_constellation(new_point: &Point, constellation: &Constellation) -> bool {
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::path::Path;
+use toml::Value;
+
+/// The known answers of a day, as stored in its `answers.toml`.
+///
+/// Answers at the top of the file belong to the `input.txt` of that day. Answers for other
+/// inputs are stored in a table keyed by the hash of that input:
+///
+/// ```toml
+/// part_1 = "595"
+/// part_2 = "80598"
+///
+/// [inputs.9f1c2ab7e04d3a51]
+/// part_1 = "502"
+/// ```
+#[derive(Debug, Default)]
+pub struct Answers {
+    default_hash: String,
+    by_hash: HashMap<String, HashMap<usize, String>>,
+}
+
+impl Answers {
+    /// Load `answers.toml` from the directory of a day. A missing file means no answers are known.
+    pub fn load(dir: &Path) -> Result<Answers, String> {
+        let default_hash = std::fs::read_to_string(dir.join("input.txt"))
+            .map(|input| hash_input(&input))
+            .unwrap_or_else(|_| hash_input(""));
+
+        match std::fs::read_to_string(dir.join("answers.toml")) {
+            Ok(content) => Answers::parse(&content, default_hash),
+            Err(_)      => Ok(Answers { default_hash, by_hash: HashMap::new() }),
+        }
+    }
+
+    /// Parse the content of an answers file, `default_hash` is the hash of the day's own input.
+    pub fn parse(content: &str, default_hash: String) -> Result<Answers, String> {
+        let value = content.parse::<Value>()
+            .map_err(|e| format!("Could not parse answers: {}", e))?;
+
+        let mut by_hash = HashMap::new();
+
+        by_hash.insert(default_hash.clone(), parse_parts(&value)?);
+
+        if let Some(inputs) = value.get("inputs") {
+            let inputs = inputs.as_table()
+                .ok_or("`inputs` should be a table")?;
+
+            for (hash, answers) in inputs {
+                by_hash.insert(hash.clone(), parse_parts(answers)?);
+            }
+        }
+
+        Ok(Answers { default_hash, by_hash })
+    }
+
+    /// The hash of the day's own input.
+    pub fn default_hash(&self) -> &str {
+        &self.default_hash
+    }
+
+    /// The expected answer of `part` for the input with hash `input_hash`, if known.
+    pub fn expected(&self, input_hash: &str, part: usize) -> Option<&str> {
+        self.by_hash.get(input_hash)
+            .and_then(|parts| parts.get(&part))
+            .map(|answer| answer.as_str())
+    }
+}
+
+/// Collect all `part_#` keys of a table, answers can be written as strings or as numbers.
+fn parse_parts(value: &Value) -> Result<HashMap<usize, String>, String> {
+    let mut parts = HashMap::new();
+
+    let table = value.as_table()
+        .ok_or("Expected a table of answers")?;
+
+    for (key, answer) in table {
+        if !key.starts_with("part_") {
+            continue;
+        }
+        let part = key["part_".len()..].parse::<usize>()
+            .map_err(|_| format!("Invalid part `{}`", key))?;
+
+        let answer = match answer {
+            Value::String(s)    => s.clone(),
+            Value::Table(_)     => return Err(format!("Answer of `{}` should be a value", key)),
+            other               => other.to_string(),
+        };
+        parts.insert(part, answer);
+    }
+    Ok(parts)
+}
+
+/// Hash the content of an input file (64-bit FNV-1a), so answers can be matched to their input.
+pub fn hash_input(input: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+
+    for byte in input.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hash_input() {
+        assert_eq!(hash_input(""), "cbf29ce484222325");
+        assert_eq!(hash_input("a"), "af63dc4c8601ec8c");
+        assert_ne!(hash_input("1\n2\n"), hash_input("2\n1\n"));
+    }
+
+    #[test]
+    fn test_parse() {
+        let content = r#"
+            part_1 = "595"
+            part_2 = 80598
+
+            [inputs.0123456789abcdef]
+            part_1 = "-3"
+        "#;
+        let answers = Answers::parse(content, "cbf29ce484222325".to_string()).unwrap();
+
+        assert_eq!(answers.expected("cbf29ce484222325", 1), Some("595"));
+        assert_eq!(answers.expected("cbf29ce484222325", 2), Some("80598"));
+        assert_eq!(answers.expected("0123456789abcdef", 1), Some("-3"));
+        assert_eq!(answers.expected("0123456789abcdef", 2), None);
+        assert_eq!(answers.expected("ffffffffffffffff", 1), None);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(Answers::parse("part_1 = ", String::new()).is_err());
+        assert!(Answers::parse("part_one = 5", String::new()).is_err());
+        assert!(Answers::parse("[part_1]\nx = 5", String::new()).is_err());
+    }
+}
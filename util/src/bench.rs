@@ -0,0 +1,282 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Timings of a number of runs of the same step.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+}
+
+impl Stats {
+    /// Calculate the stats of a list of timings, returns None if the list is empty.
+    pub fn from(mut timings: Vec<Duration>) -> Option<Stats> {
+        if timings.is_empty() {
+            return None;
+        }
+        timings.sort();
+
+        let runs = timings.len();
+        let median = if runs % 2 == 1 {
+            timings[runs / 2]
+        } else {
+            (timings[runs / 2 - 1] + timings[runs / 2]) / 2
+        };
+
+        Some(Stats { runs, min: timings[0], median })
+    }
+}
+
+/// Run `f` `warmup` times without timing it, then time `runs` runs of it. Each run gets a fresh
+/// value from `setup`, which is not timed.
+pub fn measure<S, F, I, R>(warmup: usize, runs: usize, setup: S, f: F) -> Option<Stats>
+    where S: Fn() -> I, F: Fn(I) -> R
+{
+    match try_measure(warmup, runs, setup, |input| Ok::<R, ()>(f(input))) {
+        Ok(stats)   => stats,
+        Err(())     => unreachable!("Runs of `measure` don't fail"),
+    }
+}
+
+/// Like `measure`, for runs that can fail. The first failed run stops the measurement and its
+/// error is returned instead: its duration isn't a timing of the work being measured.
+pub fn try_measure<S, F, I, R, E>(warmup: usize, runs: usize, setup: S, f: F) -> Result<Option<Stats>, E>
+    where S: Fn() -> I, F: Fn(I) -> Result<R, E>
+{
+    for _ in 0..warmup {
+        f(setup())?;
+    }
+
+    let mut timings = Vec::with_capacity(runs);
+
+    for _ in 0..runs {
+        let input = setup();
+
+        let start = Instant::now();
+        let result = f(input)?;
+        let elapsed = start.elapsed();
+
+        drop(result);
+        timings.push(elapsed);
+    }
+
+    Ok(Stats::from(timings))
+}
+
+/// A single line of the history file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Record {
+    pub timestamp: u64,
+    pub input_hash: String,
+    pub step: String,
+    pub stats: Stats,
+}
+
+impl Record {
+    fn parse(line: &str) -> Option<Record> {
+        let fields: Vec<&str> = line.split(',').collect();
+
+        if fields.len() != 6 {
+            return None;
+        }
+
+        Some(Record {
+            timestamp: fields[0].parse().ok()?,
+            input_hash: fields[1].to_string(),
+            step: fields[2].to_string(),
+            stats: Stats {
+                runs: fields[3].parse().ok()?,
+                min: Duration::from_nanos(fields[4].parse().ok()?),
+                median: Duration::from_nanos(fields[5].parse().ok()?),
+            },
+        })
+    }
+
+    fn to_line(&self) -> String {
+        format!("{},{},{},{},{},{}",
+            self.timestamp, self.input_hash, self.step,
+            self.stats.runs, self.stats.min.as_nanos(), self.stats.median.as_nanos())
+    }
+}
+
+/// The benchmark results of a day, stored as CSV in its `bench_history.csv`.
+pub struct History {
+    file: PathBuf,
+    records: Vec<Record>,
+}
+
+impl History {
+    pub const HEADER: &'static str = "timestamp,input_hash,step,runs,min_ns,median_ns";
+
+    /// Load the history in `dir`, a missing file is an empty history. Lines that can't be
+    /// parsed are ignored.
+    pub fn load(dir: &Path) -> History {
+        let file = dir.join("bench_history.csv");
+
+        let records = std::fs::read_to_string(&file)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(Record::parse)
+            .collect();
+
+        History { file, records }
+    }
+
+    /// The most recent record of `step` for the input with `input_hash`.
+    pub fn previous(&self, input_hash: &str, step: &str) -> Option<&Record> {
+        self.records.iter()
+            .rev()
+            .find(|record| record.input_hash == input_hash && record.step == step)
+    }
+
+    /// Append records to the history file.
+    pub fn append(&mut self, records: Vec<Record>) -> Result<(), String> {
+        let is_new = !self.file.exists();
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.file)
+            .map_err(|e| format!("Could not open {}: {}", self.file.display(), e))?;
+
+        let mut content = String::new();
+        if is_new {
+            content.push_str(History::HEADER);
+            content.push('\n');
+        }
+        for record in &records {
+            content.push_str(&record.to_line());
+            content.push('\n');
+        }
+
+        file.write_all(content.as_bytes())
+            .map_err(|e| format!("Could not write {}: {}", self.file.display(), e))?;
+
+        self.records.extend(records);
+        Ok(())
+    }
+}
+
+/// Differences smaller than this are considered noise, whatever the percentage.
+const NOISE_FLOOR: Duration = Duration::from_micros(100);
+
+/// Returns how much slower (in percent) `current` is compared to `previous`, if that is more
+/// than `threshold` percent. The minimum is compared, as it is the least noisy.
+pub fn regression(previous: &Stats, current: &Stats, threshold: f64) -> Option<f64> {
+    if current.min < previous.min + NOISE_FLOOR {
+        return None;
+    }
+
+    let previous = previous.min.as_nanos() as f64;
+    let current = current.min.as_nanos() as f64;
+
+    if previous <= 0.0 {
+        return None;
+    }
+
+    let slower = (current - previous) / previous * 100.0;
+
+    if slower > threshold {
+        Some(slower)
+    } else {
+        None
+    }
+}
+
+/// Seconds since the epoch, to timestamp records.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn stats(min_ms: u64, median_ms: u64) -> Stats {
+        Stats { runs: 5, min: Duration::from_millis(min_ms), median: Duration::from_millis(median_ms) }
+    }
+
+    #[test]
+    fn test_stats_from() {
+        let ms = |ms| Duration::from_millis(ms);
+
+        assert_eq!(Stats::from(vec![]), None);
+        assert_eq!(Stats::from(vec![ms(5), ms(1), ms(3)]), Some(Stats { runs: 3, min: ms(1), median: ms(3) }));
+        assert_eq!(Stats::from(vec![ms(4), ms(1), ms(2), ms(8)]), Some(Stats { runs: 4, min: ms(1), median: ms(3) }));
+    }
+
+    #[test]
+    fn test_measure() {
+        let stats = measure(2, 3, || 10, |n| (0..n).sum::<i32>()).unwrap();
+
+        assert_eq!(stats.runs, 3);
+        assert!(stats.min <= stats.median);
+
+        assert_eq!(measure(1, 0, || 10, |n| n), None);
+    }
+
+    #[test]
+    fn test_try_measure() {
+        let stats = try_measure(1, 3, || 10, |n| Ok::<i32, String>(n + 1)).unwrap().unwrap();
+        assert_eq!(stats.runs, 3);
+
+        let runs = std::cell::Cell::new(0);
+        let result = try_measure(1, 3, || (), |_| {
+            runs.set(runs.get() + 1);
+            if runs.get() > 2 { Err("cancelled") } else { Ok(()) }
+        });
+
+        assert_eq!(result, Err("cancelled"));
+        assert_eq!(runs.get(), 3);
+    }
+
+    #[test]
+    fn test_record() {
+        let record = Record { timestamp: 1544000000, input_hash: "cbf29ce484222325".to_string(), step: "puzzle 1".to_string(), stats: stats(3, 4) };
+        let line = record.to_line();
+
+        assert_eq!(line, "1544000000,cbf29ce484222325,puzzle 1,5,3000000,4000000");
+        assert_eq!(Record::parse(&line), Some(record));
+        assert_eq!(Record::parse(History::HEADER), None);
+    }
+
+    #[test]
+    fn test_history() {
+        let dir = std::env::temp_dir().join("util_bench_test_history");
+        std::fs::create_dir_all(&dir).unwrap();
+        let _ = std::fs::remove_file(dir.join("bench_history.csv"));
+
+        let record = |timestamp, step: &str, min_ms| {
+            Record { timestamp, input_hash: "abc".to_string(), step: step.to_string(), stats: stats(min_ms, min_ms) }
+        };
+
+        let mut history = History::load(&dir);
+        assert_eq!(history.previous("abc", "parse"), None);
+
+        history.append(vec![record(1, "parse", 1), record(1, "puzzle 1", 10)]).unwrap();
+        history.append(vec![record(2, "parse", 2)]).unwrap();
+
+        let history = History::load(&dir);
+        assert_eq!(history.previous("abc", "parse"), Some(&record(2, "parse", 2)));
+        assert_eq!(history.previous("abc", "puzzle 1"), Some(&record(1, "puzzle 1", 10)));
+        assert_eq!(history.previous("def", "parse"), None);
+    }
+
+    #[test]
+    fn test_regression() {
+        assert_eq!(regression(&stats(100, 100), &stats(105, 200), 10.0), None);
+        assert_eq!(regression(&stats(100, 100), &stats(90, 90), 10.0), None);
+        assert_eq!(regression(&stats(100, 100), &stats(150, 150), 10.0), Some(50.0));
+        assert_eq!(regression(&stats(0, 0), &stats(150, 150), 10.0), None);
+
+        let us = |us| Stats { runs: 5, min: Duration::from_micros(us), median: Duration::from_micros(us) };
+        assert_eq!(regression(&us(2), &us(4), 10.0), None);
+        assert_eq!(regression(&us(200), &us(400), 10.0), Some(100.0));
+    }
+}
@@ -28,12 +28,8 @@ impl<T> CircularVec<T>
             return 0;
         }
 
-        if i < 0 {
-            // modulo with negative i is weird, make sure i is positive
-            return self.wrap_index(i + len);
-        } else {
-            return (i % len) as usize;
-        }
+        // unlike %, rem_euclid is never negative
+        i.rem_euclid(len) as usize
     }
 
     /// Get the element at `index`. Returns None if the list is empty.
@@ -96,6 +92,8 @@ mod tests {
         assert_eq!(list1.wrap_index(-4), 2);
         assert_eq!(list1.wrap_index(-5), 1);
         assert_eq!(list1.wrap_index(-6), 0);
+
+        assert_eq!(list1.wrap_index(-3_000_000_001), 2);
     }
 
     #[test]
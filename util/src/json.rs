@@ -0,0 +1,143 @@
+use std::fmt::{Display, Formatter, Result};
+
+/// A minimal JSON value, enough to write out results for other tools.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Build an object from key/value pairs, keeping their order.
+    pub fn object(fields: Vec<(&str, Json)>) -> Json {
+        Json::Object(fields.into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect())
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Json {
+        Json::String(s.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Json {
+        Json::String(s)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(n: usize) -> Json {
+        Json::Number(n as f64)
+    }
+}
+
+impl From<f64> for Json {
+    fn from(n: f64) -> Json {
+        Json::Number(n)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Json {
+        Json::Bool(b)
+    }
+}
+
+impl<T> From<Option<T>> for Json
+    where T: Into<Json>
+{
+    fn from(value: Option<T>) -> Json {
+        value.map_or(Json::Null, Into::into)
+    }
+}
+
+impl<T> From<Vec<T>> for Json
+    where T: Into<Json>
+{
+    fn from(values: Vec<T>) -> Json {
+        Json::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            Json::Null                      => write!(f, "null"),
+            Json::Bool(b)                   => write!(f, "{}", b),
+            Json::Number(n) if n.is_finite() => write!(f, "{}", n),
+            Json::Number(_)                 => write!(f, "null"),
+            Json::String(s)                 => write_string(f, s),
+            Json::Array(values)             => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields)            => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut Formatter, s: &str) -> Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"'                     => write!(f, "\\\"")?,
+            '\\'                    => write!(f, "\\\\")?,
+            '\n'                    => write!(f, "\\n")?,
+            '\r'                    => write!(f, "\\r")?,
+            '\t'                    => write!(f, "\\t")?,
+            c if (c as u32) < 0x20  => write!(f, "\\u{:04x}", c as u32)?,
+            c                       => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let json = Json::object(vec![
+            ("day", "day01".into()),
+            ("part", 2.into()),
+            ("answer", Json::from(Some("80598"))),
+            ("duration_ms", 1.5.into()),
+            ("expected", Json::from(None::<String>)),
+            ("diagnostics", vec!["a", "b"].into()),
+        ]);
+
+        assert_eq!(json.to_string(),
+            r#"{"day":"day01","part":2,"answer":"80598","duration_ms":1.5,"expected":null,"diagnostics":["a","b"]}"#);
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(Json::from("say \"hi\"\n\\ \u{1}").to_string(), r#""say \"hi\"\n\\ \u0001""#);
+        assert_eq!(Json::from("µs").to_string(), "\"µs\"");
+        assert_eq!(Json::Number(f64::NAN).to_string(), "null");
+    }
+}
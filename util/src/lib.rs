@@ -3,4 +3,11 @@ pub mod nom_utils;
 pub mod grid;
 pub mod vec2;
 
-pub mod circular_vec;
\ No newline at end of file
+pub mod circular_vec;
+
+pub mod answers;
+pub mod bench;
+pub mod json;
+pub mod params;
+pub mod progress;
+pub mod runner;
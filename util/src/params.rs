@@ -0,0 +1,254 @@
+use crate::answers::hash_input;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::path::Path;
+use toml::Value;
+use toml::value::Table;
+
+/// The parameters of a day, as stored in its `params.toml`.
+///
+/// Parameters at the top of the file apply to every input. Inputs that need other values
+/// override them in a table keyed by the hash of that input:
+///
+/// ```toml
+/// workers = 5
+/// base_duration = 60
+///
+/// [inputs.9f1c2ab7e04d3a51]
+/// workers = 2
+/// ```
+#[derive(Debug, Default)]
+pub struct ParamFile {
+    defaults: Table,
+    by_hash: HashMap<String, Table>,
+    overrides: Table,
+}
+
+impl ParamFile {
+    /// Load a parameter file. A missing file means there are no parameters, unless it is
+    /// `required`.
+    pub fn load(file: &Path, required: bool) -> Result<ParamFile, String> {
+        match std::fs::read_to_string(file) {
+            Ok(content)             => ParamFile::parse(&content)
+                .map_err(|e| format!("{}: {}", file.display(), e)),
+            Err(_) if !required     => Ok(ParamFile::default()),
+            Err(e)                  => Err(format!("Could not read {}: {}", file.display(), e)),
+        }
+    }
+
+    /// Parse the content of a parameter file.
+    pub fn parse(content: &str) -> Result<ParamFile, String> {
+        let mut defaults = match content.parse::<Value>() {
+            Ok(Value::Table(table)) => table,
+            Ok(_)                   => return Err("Expected a table of parameters".to_string()),
+            Err(e)                  => return Err(format!("Could not parse parameters: {}", e)),
+        };
+
+        let mut by_hash = HashMap::new();
+
+        if let Some(inputs) = defaults.remove("inputs") {
+            let inputs = match inputs {
+                Value::Table(inputs)    => inputs,
+                _                       => return Err("`inputs` should be a table".to_string()),
+            };
+
+            for (hash, params) in inputs {
+                match params {
+                    Value::Table(params)    => by_hash.insert(hash, params),
+                    _                       => return Err(format!("`inputs.{}` should be a table", hash)),
+                };
+            }
+        }
+
+        Ok(ParamFile { defaults, by_hash, overrides: Table::new() })
+    }
+
+    /// Override a parameter for all inputs with an assignment like `workers=2`. The value is
+    /// read as TOML, anything that isn't valid TOML is taken as a string.
+    pub fn set(&mut self, assignment: &str) -> Result<(), String> {
+        let mut parts = assignment.splitn(2, '=');
+
+        let name = parts.next().unwrap_or("").trim();
+        let value = parts.next()
+            .ok_or_else(|| format!("Expected `name=value`, got `{}`", assignment))?
+            .trim();
+
+        if name.is_empty() {
+            return Err(format!("Expected `name=value`, got `{}`", assignment));
+        }
+
+        let value = format!("value = {}", value).parse::<Value>()
+            .ok()
+            .and_then(|table| table.get("value").cloned())
+            .unwrap_or_else(|| Value::String(value.to_string()));
+
+        self.overrides.insert(name.to_string(), value);
+        Ok(())
+    }
+
+    /// The parameters that apply to `input`.
+    pub fn for_input(&self, input: &str) -> Params {
+        let mut values = self.defaults.clone();
+
+        if let Some(params) = self.by_hash.get(&hash_input(input)) {
+            values.extend(params.clone());
+        }
+        values.extend(self.overrides.clone());
+
+        Params { values }
+    }
+}
+
+/// The parameters for a single input.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Params {
+    values: Table,
+}
+
+impl Params {
+    /// The value of parameter `name`.
+    pub fn get<T: Param>(&self, name: &str) -> Result<T, String> {
+        let value = self.values.get(name)
+            .ok_or_else(|| format!("Missing parameter `{}`", name))?;
+
+        T::from_value(value)
+            .ok_or_else(|| format!("Invalid value for parameter `{}`: {}", name, value))
+    }
+
+    /// Whether parameter `name` is set, whatever its value.
+    pub fn contains(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    /// The value of parameter `name`, panics if it is missing or invalid.
+    pub fn require<T: Param>(&self, name: &str) -> T {
+        self.get(name).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+impl<'a> From<Vec<(&'a str, Value)>> for Params {
+    fn from(values: Vec<(&'a str, Value)>) -> Params {
+        Params { values: values.into_iter().map(|(name, value)| (name.to_string(), value)).collect() }
+    }
+}
+
+/// Types a parameter can be read as.
+pub trait Param: Sized {
+    fn from_value(value: &Value) -> Option<Self>;
+}
+
+macro_rules! integer_param {
+    ($($t:ty),*) => {
+        $(
+            impl Param for $t {
+                fn from_value(value: &Value) -> Option<$t> {
+                    value.as_integer().and_then(|n| <$t>::try_from(n).ok())
+                }
+            }
+        )*
+    };
+}
+
+integer_param!(i32, i64, isize, u32, u64, usize);
+
+impl Param for bool {
+    fn from_value(value: &Value) -> Option<bool> {
+        value.as_bool()
+    }
+}
+
+impl Param for f64 {
+    fn from_value(value: &Value) -> Option<f64> {
+        value.as_float().or_else(|| value.as_integer().map(|n| n as f64))
+    }
+}
+
+impl Param for String {
+    fn from_value(value: &Value) -> Option<String> {
+        match value {
+            Value::String(s)    => Some(s.clone()),
+            Value::Integer(n)   => Some(n.to_string()),
+            _                   => None,
+        }
+    }
+}
+
+impl<T: Param> Param for Vec<T> {
+    fn from_value(value: &Value) -> Option<Vec<T>> {
+        value.as_array()?
+            .iter()
+            .map(T::from_value)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let content = r#"
+            workers = 5
+            base_duration = 60
+
+            [inputs.cbf29ce484222325]
+            workers = 2
+        "#;
+        let file = ParamFile::parse(content).unwrap();
+
+        let params = file.for_input("some input");
+        assert_eq!(params.get::<i32>("workers"), Ok(5));
+        assert_eq!(params.get::<i32>("base_duration"), Ok(60));
+
+        let params = file.for_input("");
+        assert_eq!(params.get::<i32>("workers"), Ok(2));
+        assert_eq!(params.get::<i32>("base_duration"), Ok(60));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(ParamFile::parse("workers = ").is_err());
+        assert!(ParamFile::parse("inputs = 5").is_err());
+        assert!(ParamFile::parse("[inputs]\nabc = 5").is_err());
+    }
+
+    #[test]
+    fn test_set() {
+        let mut file = ParamFile::parse("workers = 5\nname = \"elf\"").unwrap();
+
+        file.set("workers=2").unwrap();
+        file.set("name = santa").unwrap();
+        file.set("digits=\"0123\"").unwrap();
+
+        let params = file.for_input("");
+        assert_eq!(params.get::<usize>("workers"), Ok(2));
+        assert_eq!(params.get::<String>("name"), Ok("santa".to_string()));
+        assert_eq!(params.get::<String>("digits"), Ok("0123".to_string()));
+
+        assert!(file.set("workers").is_err());
+        assert!(file.set("=5").is_err());
+    }
+
+    #[test]
+    fn test_get() {
+        let params = Params::from(vec![("start", Value::Integer(-3)), ("ratio", Value::Integer(2))]);
+
+        assert_eq!(params.get::<i32>("start"), Ok(-3));
+        assert!(params.get::<u32>("start").is_err());
+        assert_eq!(params.get::<f64>("ratio"), Ok(2.0));
+        assert_eq!(params.get::<String>("ratio"), Ok("2".to_string()));
+        assert_eq!(params.get::<i32>("missing"), Err("Missing parameter `missing`".to_string()));
+    }
+
+    #[test]
+    fn test_get_array() {
+        let params = ParamFile::parse("speeds = [1, 2, 1]\nhalves = [0.5, 1.5]").unwrap().for_input("");
+
+        assert_eq!(params.get::<Vec<i32>>("speeds"), Ok(vec![1, 2, 1]));
+        assert!(params.get::<Vec<i32>>("halves").is_err());
+        assert!(params.get::<i32>("speeds").is_err());
+        assert!(params.contains("halves"));
+        assert!(!params.contains("workers"));
+    }
+}
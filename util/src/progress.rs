@@ -0,0 +1,251 @@
+use std::cell::RefCell;
+use std::fmt::{Display, Formatter, Result};
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// How often the progress bar is redrawn.
+const RENDER_INTERVAL: Duration = Duration::from_millis(100);
+
+const BAR_WIDTH: usize = 30;
+
+/// Handed to long-running solvers so they can report how far along they are. Reporting also
+/// checks whether the solver should stop, either because it ran out of time or because it was
+/// cancelled.
+///
+/// ```
+/// use util::progress::{Progress, Cancelled};
+///
+/// fn count_to(n: u64, progress: &Progress) -> Result<u64, Cancelled> {
+///     progress.phase("counting", Some(n));
+///
+///     for i in 1..=n {
+///         progress.update(i)?;
+///     }
+///     Ok(n)
+/// }
+///
+/// assert_eq!(count_to(1_000, &Progress::silent()), Ok(1_000));
+/// ```
+pub struct Progress {
+    render: bool,
+    start: Instant,
+    deadline: Option<Instant>,
+    cancel: Arc<AtomicBool>,
+    state: RefCell<State>,
+    diagnostics: RefCell<Vec<String>>,
+}
+
+struct State {
+    phase: String,
+    current: u64,
+    total: Option<u64>,
+    last_render: Option<Instant>,
+}
+
+/// Returned by a solver that was stopped, with how far it got.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cancelled {
+    pub phase: String,
+    pub current: u64,
+    pub total: Option<u64>,
+    pub elapsed: Duration,
+}
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "cancelled after {:.1}s, {} at {}", self.elapsed.as_secs_f64(), self.phase, self.current)?;
+
+        if let Some(total) = self.total {
+            write!(f, " of {} ({:.2}%)", total, percentage(self.current, total))?;
+        }
+        Ok(())
+    }
+}
+
+impl Progress {
+    fn new(render: bool) -> Progress {
+        Progress {
+            render,
+            start: Instant::now(),
+            deadline: None,
+            cancel: Arc::new(AtomicBool::new(false)),
+            state: RefCell::new(State { phase: String::new(), current: 0, total: None, last_render: None }),
+            diagnostics: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Progress that is not shown anywhere.
+    pub fn silent() -> Progress {
+        Progress::new(false)
+    }
+
+    /// Progress that is drawn as a bar on stderr.
+    pub fn bar() -> Progress {
+        Progress::new(true)
+    }
+
+    /// Stop the solver once `timeout` has passed.
+    pub fn with_timeout(mut self, timeout: Duration) -> Progress {
+        self.deadline = Some(self.start + timeout);
+        self
+    }
+
+    /// Stop the solver once `cancel` is set, e.g. from another thread.
+    pub fn with_cancel(mut self, cancel: Arc<AtomicBool>) -> Progress {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Start a new phase of the solver, `total` is the amount of steps if known.
+    pub fn phase(&self, name: &str, total: Option<u64>) {
+        let mut state = self.state.borrow_mut();
+
+        state.phase = name.to_string();
+        state.current = 0;
+        state.total = total;
+        state.last_render = None;
+    }
+
+    /// Report that the current phase is at step `current`. Returns an error if the solver
+    /// should stop.
+    pub fn update(&self, current: u64) -> std::result::Result<(), Cancelled> {
+        self.state.borrow_mut().current = current;
+
+        let now = Instant::now();
+
+        if self.cancel.load(Ordering::Relaxed) || self.deadline.is_some_and(|deadline| now >= deadline) {
+            self.clear();
+            return Err(self.cancelled());
+        }
+
+        if self.render {
+            let due = self.state.borrow().last_render
+                .is_none_or(|last| now.duration_since(last) >= RENDER_INTERVAL);
+
+            if due {
+                self.state.borrow_mut().last_render = Some(now);
+                self.draw();
+            }
+        }
+        Ok(())
+    }
+
+    /// Log a message for the user, it is shown right away if the progress is shown and kept
+    /// as a diagnostic either way.
+    pub fn log(&self, message: &str) {
+        self.diagnostics.borrow_mut().push(message.to_string());
+
+        if self.render {
+            self.clear();
+            eprintln!("{}", message);
+        }
+    }
+
+    /// All messages logged so far.
+    pub fn diagnostics(&self) -> Vec<String> {
+        self.diagnostics.borrow().clone()
+    }
+
+    /// Remove the progress bar, e.g. before printing an answer.
+    pub fn clear(&self) {
+        if self.render && self.state.borrow().last_render.is_some() {
+            eprint!("\r{:width$}\r", "", width = BAR_WIDTH + 60);
+            self.state.borrow_mut().last_render = None;
+        }
+    }
+
+    fn cancelled(&self) -> Cancelled {
+        let state = self.state.borrow();
+
+        Cancelled {
+            phase: state.phase.clone(),
+            current: state.current,
+            total: state.total,
+            elapsed: self.start.elapsed(),
+        }
+    }
+
+    fn draw(&self) {
+        let state = self.state.borrow();
+        let elapsed = self.start.elapsed().as_secs_f64();
+
+        match state.total {
+            Some(total) => {
+                let filled = ((percentage(state.current, total) / 100.0) * BAR_WIDTH as f64) as usize;
+                let filled = filled.min(BAR_WIDTH);
+
+                eprint!("\r[{}{}] {:6.2}% {} {}/{} {:.1}s",
+                    "#".repeat(filled), ".".repeat(BAR_WIDTH - filled),
+                    percentage(state.current, total), state.phase, state.current, total, elapsed);
+            }
+            None => {
+                eprint!("\r{} {} {:.1}s", state.phase, state.current, elapsed);
+            }
+        }
+        let _ = std::io::stderr().flush();
+    }
+}
+
+fn percentage(current: u64, total: u64) -> f64 {
+    if total == 0 {
+        return 100.0;
+    }
+    current as f64 / total as f64 * 100.0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_update() {
+        let progress = Progress::silent();
+        progress.phase("counting", Some(10));
+
+        assert_eq!(progress.update(5), Ok(()));
+    }
+
+    #[test]
+    fn test_cancel() {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let progress = Progress::silent().with_cancel(cancel.clone());
+        progress.phase("counting", Some(10));
+
+        assert_eq!(progress.update(1), Ok(()));
+
+        cancel.store(true, Ordering::Relaxed);
+        let cancelled = progress.update(2).unwrap_err();
+
+        assert_eq!(cancelled.phase, "counting");
+        assert_eq!(cancelled.current, 2);
+        assert_eq!(cancelled.total, Some(10));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let progress = Progress::silent().with_timeout(Duration::from_millis(0));
+        progress.phase("waiting", None);
+
+        assert!(progress.update(1).is_err());
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let progress = Progress::silent();
+        progress.log("first");
+        progress.log("second");
+
+        assert_eq!(progress.diagnostics(), vec!["first", "second"]);
+    }
+
+    #[test]
+    fn test_cancelled_display() {
+        let cancelled = Cancelled { phase: "generations".to_string(), current: 250, total: Some(1000), elapsed: Duration::from_millis(1500) };
+        assert_eq!(cancelled.to_string(), "cancelled after 1.5s, generations at 250 of 1000 (25.00%)");
+
+        let cancelled = Cancelled { phase: "steps".to_string(), current: 7, total: None, elapsed: Duration::from_secs(2) };
+        assert_eq!(cancelled.to_string(), "cancelled after 2.0s, steps at 7");
+    }
+}
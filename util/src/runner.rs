@@ -0,0 +1,1031 @@
+use crate::answers::{Answers, hash_input};
+use crate::bench::{self, History, Record};
+use crate::json::Json;
+use crate::params::{ParamFile, Params};
+use crate::progress::{Cancelled, Progress};
+use std::convert::Infallible;
+use std::fmt::Display;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+
+/// Command line options shared by all days. Days with options of their own can `flatten` these.
+#[derive(StructOpt, Debug)]
+pub struct RunnerOpt {
+    /// Input file, defaults to the input.txt of the day
+    #[structopt(short = "i", long = "input", parse(from_os_str))]
+    pub input: Option<PathBuf>,
+
+    /// Stop a puzzle that runs longer than this many seconds
+    #[structopt(long = "timeout")]
+    pub timeout: Option<f64>,
+
+    /// Don't show the progress of long-running puzzles
+    #[structopt(short = "q", long = "quiet")]
+    pub quiet: bool,
+
+    /// Parameter file, defaults to the params.toml of the day
+    #[structopt(long = "params", parse(from_os_str))]
+    pub params: Option<PathBuf>,
+
+    /// Override a parameter, e.g. `--set workers=2`
+    #[structopt(long = "set", number_of_values = 1)]
+    pub set: Vec<String>,
+
+    /// Output format: text, or json to print one JSON object per result
+    #[structopt(long = "format", default_value = "text")]
+    pub format: Format,
+
+    #[structopt(subcommand)]
+    pub mode: Option<Mode>,
+}
+
+impl RunnerOpt {
+    /// The parameter file to use, with the `--set` overrides applied. `dir` is the directory of
+    /// the day, where the default params.toml is.
+    pub fn param_file(&self, dir: &Path) -> Result<ParamFile, String> {
+        let file = self.params.clone()
+            .unwrap_or_else(|| dir.join("params.toml"));
+
+        let mut params = ParamFile::load(&file, self.params.is_some())?;
+
+        for assignment in &self.set {
+            params.set(assignment)?;
+        }
+        Ok(params)
+    }
+}
+
+/// How the results are printed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s {
+            "text"  => Ok(Format::Text),
+            "json"  => Ok(Format::Json),
+            _       => Err(format!("Unknown format `{}`, expected text or json", s)),
+        }
+    }
+}
+
+#[derive(StructOpt, Debug)]
+pub enum Mode {
+    /// Solve all puzzles and print the answers (default)
+    #[structopt(name = "run")]
+    Run,
+
+    /// Solve all puzzles with a known answer and compare them against answers.toml
+    #[structopt(name = "verify")]
+    Verify,
+
+    /// Solve all puzzles for every input in a directory and print a table of the results
+    #[structopt(name = "batch")]
+    Batch {
+        /// Directory with inputs, defaults to inputs/<day> in the root of the repository
+        #[structopt(parse(from_os_str))]
+        dir: Option<PathBuf>,
+    },
+
+    /// Time parsing and the puzzles, and append the results to bench_history.csv
+    #[structopt(name = "bench")]
+    Bench {
+        /// Untimed runs before measuring
+        #[structopt(short = "w", long = "warmup", default_value = "1")]
+        warmup: usize,
+
+        /// Timed runs
+        #[structopt(short = "r", long = "runs", default_value = "5")]
+        runs: usize,
+
+        /// Puzzles to time, defaults to all puzzles with a known answer
+        #[structopt(short = "p", long = "puzzle")]
+        puzzles: Vec<usize>,
+
+        /// Flag steps that are this many percent slower than the previous run
+        #[structopt(short = "t", long = "threshold", default_value = "10")]
+        threshold: f64,
+    },
+}
+
+/// The outcome of verifying a single puzzle.
+#[derive(Debug, Eq, PartialEq)]
+pub enum Verdict {
+    Pass(String),
+    Mismatch { expected: String, actual: String },
+    Fail(String),
+    Solved(String),
+    Skip,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Verdict::Pass(answer)                   => write!(f, "PASS     {}", answer),
+            Verdict::Mismatch { expected, actual }  => write!(f, "MISMATCH expected {}, got {}", expected, actual),
+            Verdict::Fail(reason)                   => write!(f, "FAIL     {}", reason),
+            Verdict::Solved(answer)                 => write!(f, "SOLVED   {}", answer),
+            Verdict::Skip                           => write!(f, "SKIP     no known answer"),
+        }
+    }
+}
+
+impl Verdict {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Verdict::Mismatch { .. } | Verdict::Fail(_))
+    }
+
+    /// A short description for use in a table.
+    fn summary(&self) -> String {
+        match self {
+            Verdict::Pass(answer)                   => format!("{} (ok)", answer),
+            Verdict::Mismatch { expected, actual }  => format!("{} (expected {})", actual, expected),
+            Verdict::Fail(reason)                   => reason.clone(),
+            Verdict::Solved(answer)                 => answer.clone(),
+            Verdict::Skip                           => "-".to_string(),
+        }
+    }
+
+    /// The status, answers and error of the verdict as JSON fields.
+    fn json_fields(&self) -> Vec<(&'static str, Json)> {
+        let (status, answer, expected, error) = match self {
+            Verdict::Pass(answer)                   => ("pass", Some(answer), Some(answer), None),
+            Verdict::Mismatch { expected, actual }  => ("mismatch", Some(actual), Some(expected), None),
+            Verdict::Fail(reason)                   => ("fail", None, None, Some(reason)),
+            Verdict::Solved(answer)                 => ("solved", Some(answer), None, None),
+            Verdict::Skip                           => ("skip", None, None, None),
+        };
+
+        vec![
+            ("status", status.into()),
+            ("answer", answer.cloned().into()),
+            ("expected", expected.cloned().into()),
+            ("error", error.cloned().into()),
+        ]
+    }
+}
+
+/// The verdict of a single puzzle, with the time it took and the messages it logged.
+#[derive(Debug)]
+pub struct Outcome {
+    pub verdict: Verdict,
+    pub elapsed: Option<Duration>,
+    pub diagnostics: Vec<String>,
+}
+
+impl Outcome {
+    fn skipped() -> Outcome {
+        Outcome { verdict: Verdict::Skip, elapsed: None, diagnostics: Vec::new() }
+    }
+}
+
+/// The outcomes of all puzzles of a day for one input, with the time parsing took.
+#[derive(Debug)]
+pub struct Report {
+    pub parse_time: Option<Duration>,
+    pub puzzles: Vec<Outcome>,
+}
+
+impl Report {
+    pub fn is_failure(&self) -> bool {
+        self.puzzles.iter().any(|outcome| outcome.verdict.is_failure())
+    }
+}
+
+/// Parses an input, or explains why it couldn't.
+type Parser<T> = Box<dyn Fn(&'static str, &Params) -> Result<T, String>>;
+/// Solves a puzzle, or explains why it couldn't.
+type Solver<T> = Box<dyn Fn(&T, &Progress) -> Result<String, String>>;
+
+/// A day of the advent: how to parse its input and how to solve each of its puzzles.
+pub struct Day<T> {
+    dir: PathBuf,
+    parse: Parser<T>,
+    params: ParamFile,
+    puzzles: Vec<Solver<T>>,
+    timeout: Option<Duration>,
+}
+
+impl<T> Day<T> {
+    /// Create a day living in `dir`, usually `env!("CARGO_MANIFEST_DIR")`.
+    pub fn new<P>(dir: &str, parse: P) -> Day<T>
+        where P: Fn(&'static str) -> T + 'static
+    {
+        Day::with_params(dir, move |input, _| parse(input))
+    }
+
+    /// Create a day whose parsing also needs the parameters of the input, as configured in
+    /// its `params.toml`.
+    pub fn with_params<P>(dir: &str, parse: P) -> Day<T>
+        where P: Fn(&'static str, &Params) -> T + 'static
+    {
+        Day::try_with_params(dir, move |input, params| Ok::<T, Infallible>(parse(input, params)))
+    }
+
+    /// Create a day whose parsing needs the parameters of the input and might fail, e.g. if the
+    /// parameters don't make sense. The error is reported for every puzzle.
+    pub fn try_with_params<P, E>(dir: &str, parse: P) -> Day<T>
+        where P: Fn(&'static str, &Params) -> Result<T, E> + 'static, E: Display
+    {
+        Day {
+            dir: PathBuf::from(dir),
+            parse: Box::new(move |input, params| parse(input, params).map_err(|e| format!("error: {}", e))),
+            params: ParamFile::default(),
+            puzzles: Vec::new(),
+            timeout: None,
+        }
+    }
+
+    /// Add the next puzzle of this day.
+    pub fn puzzle<F, A>(mut self, solve: F) -> Day<T>
+        where F: Fn(&T) -> A + 'static, A: Display
+    {
+        self.puzzles.push(Box::new(move |input, _| Ok(solve(input).to_string())));
+        self
+    }
+
+    /// Add the next puzzle of this day, for puzzles that might not have an answer.
+    pub fn try_puzzle<F, A, E>(mut self, solve: F) -> Day<T>
+        where F: Fn(&T) -> Result<A, E> + 'static, A: Display, E: Display
+    {
+        self.puzzles.push(Box::new(move |input, _| {
+            solve(input)
+                .map(|a| a.to_string())
+                .map_err(|e| format!("error: {}", e))
+        }));
+        self
+    }
+
+    /// Add the next puzzle of this day, for long-running puzzles that report their progress
+    /// and can be cancelled.
+    pub fn puzzle_with_progress<F, A>(mut self, solve: F) -> Day<T>
+        where F: Fn(&T, &Progress) -> Result<A, Cancelled> + 'static, A: Display
+    {
+        self.puzzles.push(Box::new(move |input, progress| {
+            solve(input, progress)
+                .map(|a| a.to_string())
+                .map_err(|cancelled| cancelled.to_string())
+        }));
+        self
+    }
+
+    /// Add the next puzzle of this day, for puzzles that report their progress and might not
+    /// have an answer.
+    pub fn try_puzzle_with_progress<F, A, E>(mut self, solve: F) -> Day<T>
+        where F: Fn(&T, &Progress) -> Result<A, E> + 'static, A: Display, E: Display
+    {
+        self.puzzles.push(Box::new(move |input, progress| {
+            solve(input, progress)
+                .map(|a| a.to_string())
+                .map_err(|e| format!("error: {}", e))
+        }));
+        self
+    }
+
+    /// Use the parameters of `params` instead of those in `params.toml`.
+    pub fn with_param_file(mut self, params: ParamFile) -> Day<T> {
+        self.params = params;
+        self
+    }
+
+    /// Stop puzzles that take longer than `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Day<T> {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    fn progress(&self, render: bool) -> Progress {
+        let progress = if render { Progress::bar() } else { Progress::silent() };
+
+        match self.timeout {
+            Some(timeout)   => progress.with_timeout(timeout),
+            None            => progress,
+        }
+    }
+
+    /// The name of the day, taken from its directory.
+    pub fn name(&self) -> String {
+        self.dir.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Parse the command line and run the day.
+    pub fn run(self) {
+        self.run_with(RunnerOpt::from_args());
+    }
+
+    /// Run the day with options that have already been parsed.
+    pub fn run_with(mut self, opt: RunnerOpt) {
+        if let Some(timeout) = opt.timeout {
+            self.timeout = Some(Duration::from_secs_f64(timeout));
+        }
+
+        self.params = match opt.param_file(&self.dir) {
+            Ok(params)  => params,
+            Err(e)      => {
+                eprintln!("{}", e);
+                std::process::exit(2);
+            }
+        };
+
+        let input_file = opt.input.clone()
+            .unwrap_or_else(|| self.dir.join("input.txt"));
+
+        let input = match read_input(&input_file, opt.input.is_some()) {
+            Ok(input)   => input,
+            Err(e)      => {
+                eprintln!("{}", e);
+                std::process::exit(2);
+            }
+        };
+
+        let input_name = file_name(&input_file);
+
+        match (opt.mode.unwrap_or(Mode::Run), opt.format) {
+            (Mode::Run, Format::Text)   => self.solve_all(input, !opt.quiet),
+            (Mode::Run, Format::Json)   => {
+                let answers = self.load_answers();
+
+                silence_panics();
+                let report = self.check(input, &answers, false);
+
+                self.print_json(&input_name, input, &report);
+            }
+            (Mode::Verify, format)      => {
+                let answers = self.load_answers();
+
+                silence_panics();
+                let report = self.check(input, &answers, true);
+
+                match format {
+                    Format::Text    => {
+                        for (part, outcome) in report.puzzles.iter().enumerate() {
+                            println!("{} puzzle {}: {}", self.name(), part + 1, outcome.verdict);
+                        }
+                    }
+                    Format::Json    => self.print_json(&input_name, input, &report),
+                }
+
+                if report.is_failure() {
+                    std::process::exit(1);
+                }
+            }
+            (Mode::Batch { dir }, format) => {
+                let dir = dir.unwrap_or_else(|| self.default_inputs_dir());
+                let answers = self.load_answers();
+
+                silence_panics();
+                if !self.batch(&dir, &answers, format) {
+                    std::process::exit(1);
+                }
+            }
+            (Mode::Bench { warmup, runs, puzzles, threshold }, format) => {
+                let answers = self.load_answers();
+
+                let puzzles = if puzzles.is_empty() {
+                    self.puzzles_with_answer(input, &answers)
+                } else {
+                    puzzles
+                };
+
+                if let Some(part) = puzzles.iter().find(|&&part| part == 0 || part > self.puzzles.len()) {
+                    eprintln!("{} has no puzzle {}", self.name(), part);
+                    std::process::exit(2);
+                }
+
+                if !self.bench(input, &puzzles, warmup, runs, threshold, format) {
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+
+    /// Parse `input` with its parameters.
+    fn parse(&self, input: &'static str) -> Result<T, String> {
+        (self.parse)(input, &self.params.for_input(input))
+    }
+
+    /// The puzzles with a known answer for `input`, or all puzzles if none are known.
+    fn puzzles_with_answer(&self, input: &str, answers: &Answers) -> Vec<usize> {
+        let hash = hash_input(input);
+
+        let known: Vec<usize> = (1..=self.puzzles.len())
+            .filter(|&part| answers.expected(&hash, part).is_some())
+            .collect();
+
+        if known.is_empty() {
+            (1..=self.puzzles.len()).collect()
+        } else {
+            known
+        }
+    }
+
+    fn load_answers(&self) -> Answers {
+        Answers::load(&self.dir).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        })
+    }
+
+    /// Teammates' inputs live in `inputs/<day>/`, next to the directories of the days.
+    fn default_inputs_dir(&self) -> PathBuf {
+        self.dir.parent()
+            .unwrap_or(&self.dir)
+            .join("inputs")
+            .join(self.name())
+    }
+
+    fn solve_all(&self, input: &'static str, render: bool) {
+        let parsed = match self.parse(input) {
+            Ok(parsed)  => parsed,
+            Err(reason) => {
+                for part in 1..=self.puzzles.len() {
+                    println!("Puzzle {}: {}", part, reason);
+                }
+                return;
+            }
+        };
+
+        for (part, solve) in self.puzzles.iter().enumerate() {
+            let progress = self.progress(render);
+            let answer = solve(&parsed, &progress);
+            progress.clear();
+
+            match answer {
+                Ok(answer)      => println!("Puzzle {}: {}", part + 1, answer),
+                Err(reason)     => println!("Puzzle {}: {}", part + 1, reason),
+            }
+        }
+    }
+
+    /// Solve every puzzle with a known answer and compare the result, panics are caught and
+    /// reported as failures.
+    pub fn verify(&self, input: &'static str, answers: &Answers) -> Vec<Verdict> {
+        self.check(input, answers, true).puzzles
+            .into_iter()
+            .map(|outcome| outcome.verdict)
+            .collect()
+    }
+
+    /// Solve the puzzles and compare them with the known answers. Puzzles without a known
+    /// answer are skipped if `skip_unknown` is set.
+    pub fn check(&self, input: &'static str, answers: &Answers, skip_unknown: bool) -> Report {
+        let hash = hash_input(input);
+
+        let expected: Vec<Option<&str>> = (1..=self.puzzles.len())
+            .map(|part| answers.expected(&hash, part))
+            .collect();
+
+        let skipped = |expected: &Option<&str>| skip_unknown && expected.is_none();
+
+        if expected.iter().all(skipped) {
+            return Report { parse_time: None, puzzles: expected.iter().map(|_| Outcome::skipped()).collect() };
+        }
+
+        let start = Instant::now();
+        let parsed = match catch_panic(|| self.parse(input)) {
+            Ok(Ok(parsed))  => Ok(parsed),
+            Ok(Err(reason)) => Err(reason),
+            Err(reason)     => Err(format!("parsing panicked: {}", reason)),
+        };
+        let parsed = match parsed {
+            Ok(parsed)  => parsed,
+            Err(reason) => {
+                let puzzles = expected.iter()
+                    .map(|expected| {
+                        if skipped(expected) {
+                            Outcome::skipped()
+                        } else {
+                            Outcome { verdict: Verdict::Fail(reason.clone()), elapsed: None, diagnostics: Vec::new() }
+                        }
+                    })
+                    .collect();
+                return Report { parse_time: Some(start.elapsed()), puzzles };
+            }
+        };
+        let parse_time = Some(start.elapsed());
+
+        let puzzles = self.puzzles.iter()
+            .zip(expected.iter())
+            .map(|(solve, expected)| {
+                if skipped(expected) {
+                    return Outcome::skipped();
+                }
+
+                let start = Instant::now();
+                let progress = self.progress(false);
+
+                let verdict = match (catch_panic(|| solve(&parsed, &progress)), expected) {
+                    (Ok(Err(reason)), _)            => Verdict::Fail(reason),
+                    (Ok(Ok(actual)), None)          => Verdict::Solved(actual),
+                    (Ok(Ok(actual)), Some(expected)) => {
+                        if actual == *expected {
+                            Verdict::Pass(actual)
+                        } else {
+                            Verdict::Mismatch { expected: expected.to_string(), actual }
+                        }
+                    }
+                    (Err(reason), _)                => Verdict::Fail(format!("panicked: {}", reason)),
+                };
+                Outcome { verdict, elapsed: Some(start.elapsed()), diagnostics: progress.diagnostics() }
+            })
+            .collect();
+
+        Report { parse_time, puzzles }
+    }
+
+    /// One JSON object per puzzle of the report, so results can be processed line by line.
+    fn json_records(&self, input_name: &str, input: &str, report: &Report) -> Vec<Json> {
+        let hash = hash_input(input);
+
+        report.puzzles.iter()
+            .enumerate()
+            .map(|(part, outcome)| {
+                let mut fields = vec![
+                    ("day", self.name().into()),
+                    ("input", input_name.into()),
+                    ("input_hash", hash.as_str().into()),
+                    ("part", (part + 1).into()),
+                ];
+                fields.extend(outcome.verdict.json_fields());
+                fields.push(("duration_ms", millis(outcome.elapsed)));
+                fields.push(("parse_ms", millis(report.parse_time)));
+                fields.push(("diagnostics", outcome.diagnostics.clone().into()));
+
+                Json::object(fields)
+            })
+            .collect()
+    }
+
+    fn print_json(&self, input_name: &str, input: &str, report: &Report) {
+        for record in self.json_records(input_name, input, report) {
+            println!("{}", record);
+        }
+    }
+
+    /// Solve the puzzles for every file in `dir` and print a table with the answers and timings.
+    /// Rows with a panic or an answer that differs from the known answer are marked with `!`.
+    /// Returns false if any row is marked.
+    pub fn batch(&self, dir: &Path, answers: &Answers, format: Format) -> bool {
+        let files = match list_inputs(dir) {
+            Ok(files)   => files,
+            Err(e)      => {
+                eprintln!("{}", e);
+                return false;
+            }
+        };
+
+        let mut header = vec!["input".to_string(), "parse".to_string()];
+        for part in 1..=self.puzzles.len() {
+            header.push(format!("puzzle {}", part));
+            header.push("time".to_string());
+        }
+
+        let mut rows = vec![header];
+        let mut marks = vec![false];
+
+        for file in files {
+            let name = file_name(&file);
+
+            let input = match read_input(&file, true) {
+                Ok(input)   => input,
+                Err(e)      => {
+                    if format == Format::Json {
+                        println!("{}", Json::object(vec![
+                            ("day", self.name().into()),
+                            ("input", name.as_str().into()),
+                            ("status", "fail".into()),
+                            ("error", e.as_str().into()),
+                        ]));
+                    }
+                    rows.push(vec![name, e]);
+                    marks.push(true);
+                    continue;
+                }
+            };
+
+            let report = self.check(input, answers, false);
+
+            if format == Format::Json {
+                self.print_json(&name, input, &report);
+            }
+
+            let mut row = vec![name, format_duration(report.parse_time)];
+            for outcome in &report.puzzles {
+                row.push(outcome.verdict.summary());
+                row.push(format_duration(outcome.elapsed));
+            }
+            rows.push(row);
+            marks.push(report.is_failure());
+        }
+
+        if format == Format::Text {
+            print_table(&rows, &marks);
+        }
+
+        !marks.contains(&true)
+    }
+
+    /// Time parsing and each of `puzzles`, print the min and median and compare them with the
+    /// previous results in the history. Returns false if a step got slower than `threshold`.
+    pub fn bench(&self, input: &'static str, puzzles: &[usize], warmup: usize, runs: usize, threshold: f64, format: Format) -> bool {
+        let hash = hash_input(input);
+        let mut history = History::load(&self.dir);
+
+        if format == Format::Text {
+            println!("{}: {} warm-up, {} timed runs", self.name(), warmup, runs);
+        }
+
+        let mut results = Vec::new();
+
+        let parsed = match self.parse(input) {
+            Ok(parsed)  => parsed,
+            Err(reason) => {
+                eprintln!("{}: nothing timed: {}", self.name(), reason);
+                return false;
+            }
+        };
+
+        let params = self.params.for_input(input);
+
+        if let Ok(Some(stats)) = bench::try_measure(warmup, runs, || input, |input| (self.parse)(input, &params)) {
+            results.push(("parse".to_string(), stats));
+        }
+
+        for &part in puzzles {
+            let solve = &self.puzzles[part - 1];
+
+            // every run gets its own deadline, a run that fails or times out isn't recorded
+            let measured = bench::try_measure(
+                warmup, runs,
+                || (&parsed, self.progress(false)),
+                |(parsed, progress)| solve(parsed, &progress),
+            );
+
+            match measured {
+                Ok(Some(stats)) => results.push((format!("puzzle {}", part), stats)),
+                Ok(None)        => {}
+                Err(reason)     => eprintln!("{}: puzzle {} not timed: {}", self.name(), part, reason),
+            }
+        }
+
+        let mut rows = vec![vec!["step".to_string(), "min".to_string(), "median".to_string(), "previous min".to_string()]];
+        let mut marks = vec![false];
+
+        for (step, stats) in &results {
+            let previous = history.previous(&hash, step);
+
+            let mut row = vec![
+                step.clone(),
+                format_duration(Some(stats.min)),
+                format_duration(Some(stats.median)),
+                format_duration(previous.map(|record| record.stats.min)),
+            ];
+
+            let slower = previous.and_then(|record| bench::regression(&record.stats, stats, threshold));
+            if let Some(slower) = slower {
+                row.push(format!("{:.1}% slower", slower));
+            }
+
+            if format == Format::Json {
+                println!("{}", Json::object(vec![
+                    ("day", self.name().into()),
+                    ("input_hash", hash.as_str().into()),
+                    ("step", step.as_str().into()),
+                    ("runs", stats.runs.into()),
+                    ("min_ms", millis(Some(stats.min))),
+                    ("median_ms", millis(Some(stats.median))),
+                    ("previous_min_ms", millis(previous.map(|record| record.stats.min))),
+                    ("slower_percent", slower.into()),
+                ]));
+            }
+
+            rows.push(row);
+            marks.push(slower.is_some());
+        }
+
+        if format == Format::Text {
+            print_table(&rows, &marks);
+        }
+
+        let timestamp = bench::now();
+        let records = results.into_iter()
+            .map(|(step, stats)| Record { timestamp, input_hash: hash.clone(), step, stats })
+            .collect();
+
+        if let Err(e) = history.append(records) {
+            eprintln!("{}", e);
+        }
+
+        !marks.contains(&true)
+    }
+}
+
+/// All files in `dir`, sorted by name.
+fn list_inputs(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| format!("Could not read {}: {}", dir.display(), e))?;
+
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+
+    files.sort();
+    Ok(files)
+}
+
+/// Print `rows` as aligned columns, rows with a mark are prefixed with `!`.
+fn print_table(rows: &[Vec<String>], marks: &[bool]) {
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+
+    let widths: Vec<usize> = (0..columns)
+        .map(|c| {
+            rows.iter()
+                .filter_map(|row| row.get(c))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    for (row, mark) in rows.iter().zip(marks) {
+        let cells: Vec<String> = row.iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect();
+
+        println!("{} {}", if *mark { "!" } else { " " }, cells.join("  ").trim_end());
+    }
+}
+
+/// The name of a file, without its directory.
+fn file_name(file: &Path) -> String {
+    file.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// A duration in milliseconds, or null if the step did not run.
+fn millis(duration: Option<Duration>) -> Json {
+    duration.map(|duration| duration.as_secs_f64() * 1000.0).into()
+}
+
+/// Format a duration in milli- or microseconds, or `-` if the step did not run.
+pub fn format_duration(duration: Option<Duration>) -> String {
+    match duration {
+        Some(duration) if duration < Duration::from_millis(1) => {
+            format!("{:.1}µs", duration.as_secs_f64() * 1_000_000.0)
+        }
+        Some(duration)  => format!("{:.1}ms", duration.as_secs_f64() * 1000.0),
+        None            => "-".to_string(),
+    }
+}
+
+/// Read the input file. The days hold on to (parts of) their input while solving, so the
+/// content is leaked to give it a `'static` lifetime. A missing default input is fine, some
+/// days have their input in the code.
+fn read_input(file: &Path, required: bool) -> Result<&'static str, String> {
+    let content = match std::fs::read_to_string(file) {
+        Ok(content)                 => content,
+        Err(_) if !required         => String::new(),
+        Err(e)                      => return Err(format!("Could not read {}: {}", file.display(), e)),
+    };
+    Ok(Box::leak(content.into_boxed_str()))
+}
+
+/// Panics are caught and reported by the runner, so don't print them as well.
+fn silence_panics() {
+    panic::set_hook(Box::new(|_| {}));
+}
+
+/// Run `f`, converting a panic into an error holding the panic message.
+fn catch_panic<F, R>(f: F) -> Result<R, String>
+    where F: FnOnce() -> R
+{
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .map_err(|e| {
+            if let Some(s) = e.downcast_ref::<&str>() {
+                s.to_string()
+            } else if let Some(s) = e.downcast_ref::<String>() {
+                s.clone()
+            } else {
+                "unknown reason".to_string()
+            }
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn example_day() -> Day<Vec<i32>> {
+        Day::new("/tmp/day99", |input| {
+                input.split(',')
+                    .map(|s| s.parse::<i32>().expect("not a number"))
+                    .collect()
+            })
+            .puzzle(|numbers: &Vec<i32>| numbers.iter().sum::<i32>())
+            .puzzle(|numbers: &Vec<i32>| numbers[10])
+            .puzzle(|numbers: &Vec<i32>| numbers.len())
+    }
+
+    #[test]
+    fn test_check_params() {
+        let input = "1,2,3";
+        let answers = Answers::parse("part_1 = 12", hash_input(input)).unwrap();
+
+        let day = Day::with_params("/tmp/day99", |input: &str, params: &Params| {
+                let factor: i32 = params.require("factor");
+                input.split(',').map(|s| s.parse::<i32>().unwrap() * factor).collect::<Vec<i32>>()
+            })
+            .puzzle(|numbers: &Vec<i32>| numbers.iter().sum::<i32>());
+
+        assert!(matches!(day.check(input, &answers, false).puzzles[0].verdict, Verdict::Fail(_)));
+
+        let day = day.with_param_file(ParamFile::parse("factor = 2").unwrap());
+        assert_eq!(day.check(input, &answers, false).puzzles[0].verdict, Verdict::Pass("12".to_string()));
+    }
+
+    #[test]
+    fn test_check_parse_error() {
+        let day = Day::try_with_params("/tmp/day99", |input: &str, _: &Params| {
+                input.parse::<i32>().map_err(|_| format!("`{}` is not a number", input))
+            })
+            .puzzle(|number: &i32| number + 1)
+            .puzzle(|number: &i32| number * 2);
+
+        let report = day.check("two", &Answers::default(), false);
+        assert_eq!(report.puzzles[0].verdict, Verdict::Fail("error: `two` is not a number".to_string()));
+        assert_eq!(report.puzzles[1].verdict, Verdict::Fail("error: `two` is not a number".to_string()));
+        assert!(report.puzzles[0].elapsed.is_none());
+
+        let report = day.check("2", &Answers::default(), false);
+        assert_eq!(report.puzzles[1].verdict, Verdict::Solved("4".to_string()));
+    }
+
+    #[test]
+    fn test_check_error() {
+        let day = Day::new("/tmp/day99", |input| input.len())
+            .try_puzzle(|len: &usize| if *len > 0 { Ok(*len) } else { Err("empty input") })
+            .try_puzzle_with_progress(|len: &usize, progress: &Progress| {
+                progress.log(&format!("length {}", len));
+                if *len > 1 { Ok(*len) } else { Err("too short") }
+            });
+
+        let report = day.check("", &Answers::default(), false);
+        assert_eq!(report.puzzles[0].verdict, Verdict::Fail("error: empty input".to_string()));
+
+        let report = day.check("a", &Answers::default(), false);
+        assert_eq!(report.puzzles[1].verdict, Verdict::Fail("error: too short".to_string()));
+        assert_eq!(report.puzzles[1].diagnostics, vec!["length 1".to_string()]);
+
+        let report = day.check("abc", &Answers::default(), false);
+        assert_eq!(report.puzzles[0].verdict, Verdict::Solved("3".to_string()));
+        assert_eq!(report.puzzles[1].verdict, Verdict::Solved("3".to_string()));
+    }
+
+    #[test]
+    fn test_check_cancelled() {
+        let day = Day::new("/tmp/day99", |input| input.len() as u64)
+            .puzzle_with_progress(|len: &u64, progress: &Progress| {
+                progress.phase("looping", None);
+                let mut i = 0;
+                loop {
+                    i += 1;
+                    progress.update(i)?;
+                    if i == *len {
+                        return Ok(i);
+                    }
+                }
+            })
+            .with_timeout(Duration::from_millis(10));
+
+        let report = day.check("", &Answers::default(), false);
+
+        assert!(matches!(report.puzzles[0].verdict, Verdict::Fail(ref reason) if reason.starts_with("cancelled after")));
+    }
+
+    #[test]
+    fn test_name() {
+        assert_eq!(example_day().name(), "day99");
+    }
+
+    #[test]
+    fn test_verify() {
+        let input = "1,2,3";
+        let answers = Answers::parse("part_1 = 6\npart_2 = 1\n", hash_input(input)).unwrap();
+
+        let verdicts = example_day().verify(input, &answers);
+
+        assert_eq!(verdicts[0], Verdict::Pass("6".to_string()));
+        assert!(matches!(verdicts[1], Verdict::Fail(_)));
+        assert_eq!(verdicts[2], Verdict::Skip);
+    }
+
+    #[test]
+    fn test_verify_mismatch() {
+        let input = "1,2,3";
+        let answers = Answers::parse("part_3 = 4", hash_input(input)).unwrap();
+
+        let verdicts = example_day().verify(input, &answers);
+
+        assert_eq!(verdicts[2], Verdict::Mismatch { expected: "4".to_string(), actual: "3".to_string() });
+    }
+
+    #[test]
+    fn test_verify_other_input() {
+        let answers = Answers::parse("part_1 = 6", hash_input("1,2,3")).unwrap();
+
+        let verdicts = example_day().verify("1,2,4", &answers);
+
+        assert_eq!(verdicts, vec![Verdict::Skip, Verdict::Skip, Verdict::Skip]);
+    }
+
+    #[test]
+    fn test_check() {
+        let input = "1,2,3";
+        let answers = Answers::parse("part_1 = 5", hash_input(input)).unwrap();
+
+        let report = example_day().check(input, &answers, false);
+
+        assert!(report.parse_time.is_some());
+        assert_eq!(report.puzzles[0].verdict, Verdict::Mismatch { expected: "5".to_string(), actual: "6".to_string() });
+        assert!(matches!(report.puzzles[1].verdict, Verdict::Fail(_)));
+        assert_eq!(report.puzzles[2].verdict, Verdict::Solved("3".to_string()));
+        assert!(report.puzzles.iter().all(|outcome| outcome.elapsed.is_some()));
+        assert!(report.is_failure());
+    }
+
+    #[test]
+    fn test_json_records() {
+        let input = "1,2,3";
+        let answers = Answers::parse("part_1 = 6", hash_input(input)).unwrap();
+
+        let report = example_day().check(input, &answers, false);
+        let records: Vec<String> = example_day().json_records("input.txt", input, &report)
+            .iter()
+            .map(|record| record.to_string())
+            .collect();
+
+        assert_eq!(records.len(), 3);
+        assert!(records[0].starts_with(r#"{"day":"day99","input":"input.txt","input_hash":""#));
+        assert!(records[0].contains(r#""part":1,"status":"pass","answer":"6","expected":"6","error":null,"duration_ms":"#));
+        assert!(records[1].contains(r#""status":"fail","answer":null,"expected":null,"error":"panicked: "#));
+        assert!(records[2].contains(r#""status":"solved","answer":"3","#));
+        assert!(records[2].ends_with(r#""diagnostics":[]}"#));
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!("text".parse(), Ok(Format::Text));
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert!("yaml".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn test_list_inputs() {
+        let dir = std::env::temp_dir().join("util_runner_test_list_inputs");
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("bob.txt"), "1").unwrap();
+        std::fs::write(dir.join("alice.txt"), "2").unwrap();
+
+        let files = list_inputs(&dir).unwrap();
+
+        assert_eq!(files, vec![dir.join("alice.txt"), dir.join("bob.txt")]);
+        assert!(list_inputs(&dir.join("does_not_exist")).is_err());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Some(Duration::from_micros(12_345))), "12.3ms");
+        assert_eq!(format_duration(Some(Duration::from_nanos(45_678))), "45.7µs");
+        assert_eq!(format_duration(None), "-");
+    }
+
+    #[test]
+    fn test_read_input() {
+        assert_eq!(read_input(Path::new("does_not_exist.txt"), false), Ok(""));
+        assert!(read_input(Path::new("does_not_exist.txt"), true).is_err());
+    }
+
+    #[test]
+    fn test_verify_parse_panics() {
+        let input = "1,two,3";
+        let answers = Answers::parse("part_1 = 6", hash_input(input)).unwrap();
+
+        let verdicts = example_day().verify(input, &answers);
+
+        assert!(matches!(verdicts[0], Verdict::Fail(_)));
+    }
+}
@@ -0,0 +1,14 @@
+#!/bin/bash
+
+# Verify the answers of every day that has an answers.toml, exits with 1 if any day fails.
+
+status=0
+
+for answers in day*/answers.toml
+do
+	day=$(dirname $answers)
+
+	cargo run --release --quiet -p $day -- verify || status=1
+done
+
+exit $status